@@ -0,0 +1,7 @@
+mod std140;
+mod uniform_buffer;
+mod shader_storage_buffer;
+
+pub use std140::{Std140, Std140Writer, UniformBlock};
+pub use uniform_buffer::UniformBuffer;
+pub use shader_storage_buffer::ShaderStorageBuffer;
@@ -0,0 +1,109 @@
+extern crate gl;
+use std::marker::PhantomData;
+use std::mem::size_of;
+
+use gl::types::{GLintptr, GLsizeiptr, GLuint};
+use crate::RenderError;
+
+// An array of plain data a compute shader can read and write, e.g. particles. T has to be #[repr(C)] and match the
+// buffer block's std430 layout, so a vec3 member needs padding out to 16 bytes like it does on the GLSL side.
+pub struct ShaderStorageBuffer<T: Copy + Default> {
+    id: GLuint,
+    binding: GLuint,
+    len: usize,
+    _data: PhantomData<T>,
+}
+
+impl<T: Copy + Default> Drop for ShaderStorageBuffer<T> {
+    fn drop(&mut self) {
+        if self.id == 0 { return }
+        unsafe { gl::DeleteBuffers(1, &self.id); }
+    }
+}
+
+impl<T: Copy + Default> ShaderStorageBuffer<T> {
+    pub fn new(binding: GLuint, data: &[T]) -> Result<ShaderStorageBuffer<T>, RenderError> {
+        let mut storage_buffer = ShaderStorageBuffer { id: 0, binding, len: 0, _data: PhantomData };
+
+        unsafe { gl::GenBuffers(1, &mut storage_buffer.id); }
+        if storage_buffer.id == 0 {
+            return Err(RenderError::BufferError { error: "Failed to create shader storage buffer!".to_string() });
+        }
+
+        storage_buffer.update(data);
+        storage_buffer.bind_to(binding);
+
+        Ok(storage_buffer)
+    }
+
+    pub fn with_len(binding: GLuint, len: usize) -> Result<ShaderStorageBuffer<T>, RenderError> { // filled with T::default(), for buffers only the GPU writes
+        Self::new(binding, &vec![T::default(); len])
+    }
+
+    pub fn update(&mut self, data: &[T]) {
+        let size = size_of_val(data) as GLsizeiptr;
+
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.id);
+            if data.len() == self.len {
+                gl::BufferSubData(gl::SHADER_STORAGE_BUFFER, 0, size, data.as_ptr().cast());
+            }
+            else {
+                gl::BufferData(gl::SHADER_STORAGE_BUFFER, size, data.as_ptr().cast(), gl::DYNAMIC_COPY);
+                self.len = data.len();
+            }
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+    }
+
+    pub fn write(&mut self, start: usize, data: &[T]) -> Result<(), RenderError> { // overwrites part of the buffer, e.g. newly spawned particles
+        if start + data.len() > self.len {
+            return Err(RenderError::BufferError { error: format!("Writing {} elements at {} overflows a shader storage buffer of {}!", data.len(), start, self.len) });
+        }
+
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.id);
+            gl::BufferSubData(gl::SHADER_STORAGE_BUFFER, (start * size_of::<T>()) as GLintptr, size_of_val(data) as GLsizeiptr, data.as_ptr().cast());
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+
+        Ok(())
+    }
+
+    pub fn read_back(&self) -> Vec<T> { // stalls until the GPU is done, put a MemoryBarrier::BufferUpdate after the dispatch that writes it
+        let mut data = vec![T::default(); self.len];
+
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.id);
+            gl::GetBufferSubData(gl::SHADER_STORAGE_BUFFER, 0, size_of_val(data.as_slice()) as GLsizeiptr, data.as_mut_ptr().cast());
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+
+        data
+    }
+
+    pub fn bind_to(&mut self, binding: GLuint) { // matches layout(std430, binding = N) in the shader
+        self.binding = binding;
+        unsafe { gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding, self.id); }
+    }
+
+    pub fn bind_as_vertex_buffer(&self) { // lets a render pass draw straight from what the compute pass wrote
+        unsafe { gl::BindBuffer(gl::ARRAY_BUFFER, self.id); }
+    }
+
+    pub fn get_binding(&self) -> GLuint {
+        self.binding
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get_id(&self) -> GLuint {
+        self.id
+    }
+}
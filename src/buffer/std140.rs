@@ -0,0 +1,325 @@
+use crate::types::{IVec2, IVec3, IVec4, Mat4, UVec2, Vec2, Vec3, Vec4};
+
+// Values that can sit in a std140 uniform block, see section 7.6.2.2 of the OpenGL 4.6 spec.
+pub trait Std140 {
+    const ALIGNMENT: usize;
+    const SIZE: usize;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]); // bytes is exactly SIZE long
+}
+
+// A Rust struct that mirrors a GLSL uniform block. Write the fields in the order the block declares them:
+//
+// impl UniformBlock for CameraData {
+//     fn write_std140(&self, writer: &mut Std140Writer) {
+//         writer.write(&self.view);
+//         writer.write(&self.projection);
+//         writer.write(&self.position);
+//     }
+// }
+pub trait UniformBlock {
+    fn write_std140(&self, writer: &mut Std140Writer);
+
+    fn to_std140_bytes(&self) -> Vec<u8> {
+        let mut writer = Std140Writer::new();
+        self.write_std140(&mut writer);
+        writer.into_bytes()
+    }
+}
+
+const VEC4_ALIGNMENT: usize = 16; // arrays and structs are always aligned and padded to a vec4
+
+fn round_up(value: usize, alignment: usize) -> usize {
+    value.div_ceil(alignment) * alignment
+}
+
+fn write_words(bytes: &mut [u8], words: &[[u8; 4]]) {
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(word);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Std140Writer {
+    bytes: Vec<u8>,
+}
+
+impl Std140Writer {
+    pub fn new() -> Std140Writer {
+        Std140Writer { bytes: vec![] }
+    }
+
+    fn align_to(&mut self, alignment: usize) {
+        self.bytes.resize(round_up(self.bytes.len(), alignment), 0);
+    }
+
+    pub fn write<T: Std140>(&mut self, value: &T) -> usize { // returns the member's offset
+        self.align_to(T::ALIGNMENT);
+        let offset = self.bytes.len();
+
+        self.bytes.resize(offset + T::SIZE, 0);
+        value.write_std140_bytes(&mut self.bytes[offset..]);
+
+        offset
+    }
+
+    pub fn write_array<T: Std140>(&mut self, values: &[T]) -> usize { // each element takes up at least a whole vec4
+        self.align_to(T::ALIGNMENT.max(VEC4_ALIGNMENT));
+        let offset = self.bytes.len();
+        let stride = round_up(T::SIZE, VEC4_ALIGNMENT);
+
+        self.bytes.resize(offset + stride * values.len(), 0);
+        for (index, value) in values.iter().enumerate() {
+            let start = offset + stride * index;
+            value.write_std140_bytes(&mut self.bytes[start..start + T::SIZE]);
+        }
+
+        offset
+    }
+
+    pub fn write_struct<S: UniformBlock>(&mut self, value: &S) -> usize { // a nested GLSL struct
+        self.align_to(VEC4_ALIGNMENT);
+        let offset = self.bytes.len();
+
+        value.write_std140(self);
+        self.align_to(VEC4_ALIGNMENT); // the next member can't share the struct's last vec4
+
+        offset
+    }
+
+    pub fn write_struct_array<S: UniformBlock>(&mut self, values: &[S]) -> usize {
+        self.align_to(VEC4_ALIGNMENT);
+        let offset = self.bytes.len();
+
+        for value in values {
+            self.write_struct(value);
+        }
+
+        offset
+    }
+
+    pub fn get_offset(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        self.align_to(VEC4_ALIGNMENT);
+        self.bytes
+    }
+}
+
+
+impl Std140 for f32 {
+    const ALIGNMENT: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for i32 {
+    const ALIGNMENT: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for u32 {
+    const ALIGNMENT: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for bool { // GLSL bools are 32 bits in a block
+    const ALIGNMENT: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[(*self as u32).to_ne_bytes()]);
+    }
+}
+
+impl Std140 for Vec2 {
+    const ALIGNMENT: usize = 8;
+    const SIZE: usize = 8;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.x.to_ne_bytes(), self.y.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for Vec3 { // aligned like a vec4, but a following scalar can use the last 4 bytes
+    const ALIGNMENT: usize = 16;
+    const SIZE: usize = 12;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.x.to_ne_bytes(), self.y.to_ne_bytes(), self.z.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for Vec4 {
+    const ALIGNMENT: usize = 16;
+    const SIZE: usize = 16;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.x.to_ne_bytes(), self.y.to_ne_bytes(), self.z.to_ne_bytes(), self.w.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for IVec2 {
+    const ALIGNMENT: usize = 8;
+    const SIZE: usize = 8;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.x.to_ne_bytes(), self.y.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for IVec3 {
+    const ALIGNMENT: usize = 16;
+    const SIZE: usize = 12;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.x.to_ne_bytes(), self.y.to_ne_bytes(), self.z.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for IVec4 {
+    const ALIGNMENT: usize = 16;
+    const SIZE: usize = 16;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.x.to_ne_bytes(), self.y.to_ne_bytes(), self.z.to_ne_bytes(), self.w.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for UVec2 {
+    const ALIGNMENT: usize = 8;
+    const SIZE: usize = 8;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        write_words(bytes, &[self.x.to_ne_bytes(), self.y.to_ne_bytes()]);
+    }
+}
+
+impl Std140 for Mat4 { // laid out as an array of four column vec4s
+    const ALIGNMENT: usize = 16;
+    const SIZE: usize = 64;
+
+    fn write_std140_bytes(&self, bytes: &mut [u8]) {
+        for (column, chunk) in self.as_array().iter().zip(bytes.chunks_exact_mut(16)) {
+            column.write_std140_bytes(chunk);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod std140_tests {
+    use crate::buffer::{Std140Writer, UniformBlock};
+    use crate::types::{vec2, vec3, vec4, Mat4, Vec2, Vec3};
+
+    fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+        f32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    struct Light {
+        position: Vec3,
+        intensity: f32,
+        colour: Vec3,
+    }
+
+    impl UniformBlock for Light {
+        fn write_std140(&self, writer: &mut Std140Writer) {
+            writer.write(&self.position);
+            writer.write(&self.intensity);
+            writer.write(&self.colour);
+        }
+    }
+
+    #[test]
+    fn scalar_packs_after_vec3_test() {
+        let mut writer = Std140Writer::new();
+
+        assert_eq!(writer.write(&vec3(1.0, 2.0, 3.0)), 0);
+        assert_eq!(writer.write(&4.0_f32), 12);
+        assert_eq!(writer.write(&vec2(5.0, 6.0)), 16);
+        assert_eq!(writer.write(&vec3(7.0, 8.0, 9.0)), 32);
+
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes.len(), 48);
+        assert_eq!(read_f32(&bytes, 12), 4.0);
+        assert_eq!(read_f32(&bytes, 40), 9.0);
+    }
+
+    #[test]
+    fn vec2_alignment_test() {
+        let mut writer = Std140Writer::new();
+
+        writer.write(&1.0_f32);
+
+        assert_eq!(writer.write(&vec2(1.0, 2.0)), 8);
+        assert_eq!(writer.write(&true), 16);
+        assert_eq!(writer.write(&vec4(1.0, 2.0, 3.0, 4.0)), 32);
+    }
+
+    #[test]
+    fn array_stride_test() {
+        let mut writer = Std140Writer::new();
+        writer.write(&1.0_f32);
+
+        assert_eq!(writer.write_array(&[1.0_f32, 2.0, 3.0]), 16); // every element gets a whole vec4
+        assert_eq!(writer.write(&0.5_f32), 64);
+
+        let bytes = writer.into_bytes();
+        assert_eq!(read_f32(&bytes, 16), 1.0);
+        assert_eq!(read_f32(&bytes, 32), 2.0);
+        assert_eq!(read_f32(&bytes, 48), 3.0);
+    }
+
+    #[test]
+    fn vec2_array_stride_test() {
+        let mut writer = Std140Writer::new();
+
+        writer.write_array::<Vec2>(&[vec2(1.0, 2.0), vec2(3.0, 4.0)]);
+
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(read_f32(&bytes, 16), 3.0);
+    }
+
+    #[test]
+    fn matrix_layout_test() {
+        let mut writer = Std140Writer::new();
+        writer.write(&1.0_f32);
+
+        assert_eq!(writer.write(&Mat4::identity()), 16);
+
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes.len(), 80);
+        assert_eq!((read_f32(&bytes, 16), read_f32(&bytes, 20), read_f32(&bytes, 36)), (1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn struct_layout_test() {
+        let light = Light { position: vec3(1.0, 2.0, 3.0), intensity: 0.5, colour: vec3(1.0, 1.0, 1.0) };
+        let mut writer = Std140Writer::new();
+        writer.write(&1.0_f32);
+
+        assert_eq!(writer.write_struct(&light), 16);
+        assert_eq!(writer.write(&2.0_f32), 48); // the struct is padded out to a multiple of 16
+        assert_eq!(light.to_std140_bytes().len(), 32);
+        assert_eq!(writer.write_struct_array(&[Light { position: vec3(0.0, 0.0, 0.0), intensity: 1.0, colour: vec3(0.0, 0.0, 0.0) }, light]), 64);
+
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes.len(), 128);
+        assert_eq!(read_f32(&bytes, 28), 0.5);
+        assert_eq!(read_f32(&bytes, 96 + 12), 0.5);
+    }
+}
@@ -0,0 +1,69 @@
+extern crate gl;
+use std::marker::PhantomData;
+
+use gl::types::{GLsizeiptr, GLuint};
+use crate::buffer::UniformBlock;
+use crate::RenderError;
+
+pub struct UniformBuffer<T: UniformBlock> { // a uniform block's data on the GPU, shared by every shader bound to the same binding point
+    id: GLuint,
+    binding: GLuint,
+    size: usize,
+    _data: PhantomData<T>,
+}
+
+impl<T: UniformBlock> Drop for UniformBuffer<T> {
+    fn drop(&mut self) {
+        if self.id == 0 { return }
+        unsafe { gl::DeleteBuffers(1, &self.id); }
+    }
+}
+
+impl<T: UniformBlock> UniformBuffer<T> {
+    pub fn new(binding: GLuint, data: &T) -> Result<UniformBuffer<T>, RenderError> {
+        let mut uniform_buffer = UniformBuffer { id: 0, binding, size: 0, _data: PhantomData };
+
+        unsafe { gl::GenBuffers(1, &mut uniform_buffer.id); }
+        if uniform_buffer.id == 0 {
+            return Err(RenderError::BufferError { error: "Failed to create uniform buffer!".to_string() });
+        }
+
+        uniform_buffer.update(data);
+        uniform_buffer.bind_to(binding);
+
+        Ok(uniform_buffer)
+    }
+
+    pub fn update(&mut self, data: &T) { // call once per frame and every shader using the block sees it
+        let bytes = data.to_std140_bytes();
+
+        unsafe {
+            gl::BindBuffer(gl::UNIFORM_BUFFER, self.id);
+            if bytes.len() == self.size {
+                gl::BufferSubData(gl::UNIFORM_BUFFER, 0, bytes.len() as GLsizeiptr, bytes.as_ptr().cast());
+            }
+            else { // first upload, or a struct that writes a varying amount, e.g. a light list
+                gl::BufferData(gl::UNIFORM_BUFFER, bytes.len() as GLsizeiptr, bytes.as_ptr().cast(), gl::DYNAMIC_DRAW);
+                self.size = bytes.len();
+            }
+            gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
+        }
+    }
+
+    pub fn bind_to(&mut self, binding: GLuint) {
+        self.binding = binding;
+        unsafe { gl::BindBufferBase(gl::UNIFORM_BUFFER, binding, self.id); }
+    }
+
+    pub fn get_binding(&self) -> GLuint {
+        self.binding
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get_id(&self) -> GLuint {
+        self.id
+    }
+}
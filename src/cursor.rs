@@ -0,0 +1,98 @@
+use std::path::Path;
+use image::RgbaImage;
+use glfw::StandardCursor;
+use crate::RenderError;
+use crate::types::{uvec2, UVec2};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorShape {
+    Arrow,
+    IBeam,
+    Crosshair,
+    Hand,
+    HorizontalResize,
+    VerticalResize,
+}
+
+impl From<CursorShape> for StandardCursor {
+    fn from(shape: CursorShape) -> Self {
+        match shape {
+            CursorShape::Arrow => StandardCursor::Arrow,
+            CursorShape::IBeam => StandardCursor::IBeam,
+            CursorShape::Crosshair => StandardCursor::Crosshair,
+            CursorShape::Hand => StandardCursor::Hand,
+            CursorShape::HorizontalResize => StandardCursor::HResize,
+            CursorShape::VerticalResize => StandardCursor::VResize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomCursor {
+    image: RgbaImage, // top row first, unlike texture data which is flipped for GL
+    hotspot: UVec2, // in pixels from the top left
+}
+
+impl CustomCursor {
+    pub fn from_file(path: &Path, hotspot: UVec2) -> Result<CustomCursor, RenderError> {
+        match image::open(path) {
+            Ok(image) => Self::from_image(image.into_rgba8(), hotspot),
+            Err(e) => Err(RenderError::TextureError { texture_path: path.to_string_lossy().to_string(), error: e.to_string() }),
+        }
+    }
+
+    pub fn from_raw_data(data: &[u8], size: UVec2, hotspot: UVec2) -> Result<CustomCursor, RenderError> {
+        match RgbaImage::from_raw(size.x, size.y, data.to_vec()) {
+            Some(image) => Self::from_image(image, hotspot),
+            None => Err(RenderError::CursorError { error: format!("Cursor data is {} bytes but a {}x{} RGBA image needs {}", data.len(), size.x, size.y, size.x as usize * size.y as usize * 4) }),
+        }
+    }
+
+    fn from_image(image: RgbaImage, hotspot: UVec2) -> Result<CustomCursor, RenderError> {
+        if image.width() == 0 || image.height() == 0 {
+            return Err(RenderError::CursorError { error: "Cursor image is empty".to_string() });
+        }
+        if hotspot.x >= image.width() || hotspot.y >= image.height() {
+            return Err(RenderError::CursorError { error: format!("Cursor hotspot {} is outside the {}x{} image", hotspot, image.width(), image.height()) });
+        }
+
+        Ok(CustomCursor { image, hotspot })
+    }
+
+    pub fn get_size(&self) -> UVec2 {
+        uvec2(self.image.width(), self.image.height())
+    }
+
+    pub fn get_hotspot(&self) -> UVec2 {
+        self.hotspot
+    }
+
+    pub(crate) fn create_glfw_cursor(&self) -> glfw::Cursor {
+        glfw::Cursor::create(self.image.clone(), self.hotspot.x, self.hotspot.y)
+    }
+}
+
+
+#[cfg(test)]
+mod cursor_test {
+    use crate::cursor::CustomCursor;
+    use crate::types::uvec2;
+
+    #[test]
+    fn from_raw_data_test() {
+        let cursor = CustomCursor::from_raw_data(&[255; 2 * 2 * 4], uvec2(2, 2), uvec2(1, 0)).unwrap();
+
+        assert_eq!(cursor.get_size(), uvec2(2, 2));
+        assert_eq!(cursor.get_hotspot(), uvec2(1, 0));
+    }
+
+    #[test]
+    fn from_raw_data_wrong_length_test() {
+        assert!(CustomCursor::from_raw_data(&[255; 10], uvec2(2, 2), uvec2(0, 0)).is_err());
+    }
+
+    #[test]
+    fn from_raw_data_hotspot_outside_image_test() {
+        assert!(CustomCursor::from_raw_data(&[255; 2 * 2 * 4], uvec2(2, 2), uvec2(2, 0)).is_err());
+    }
+}
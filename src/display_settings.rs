@@ -0,0 +1,266 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::monitor::{find_closest_video_mode, DisplayMode, MonitorInfo, MonitorSelection, VideoModeRequest};
+use crate::RenderError;
+use crate::types::{uvec2, IVec2, UVec2};
+
+const MIN_WINDOW_SIZE: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FullscreenSetting {
+    Windowed,
+    Fullscreen,
+    Borderless,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)] // missing keys fall back to the defaults so old files keep loading
+pub struct DisplaySettings {
+    pub window_x: Option<i32>, // None lets the OS place the window
+    pub window_y: Option<i32>,
+    pub window_width: u32,
+    pub window_height: u32,
+    pub fullscreen: FullscreenSetting,
+    pub monitor: Option<String>, // None uses whichever monitor the window is on
+    pub fullscreen_width: Option<u32>, // None keeps the monitor's current video mode
+    pub fullscreen_height: Option<u32>,
+    pub refresh_rate: Option<u32>,
+    pub vsync: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        DisplaySettings {
+            window_x: None,
+            window_y: None,
+            window_width: 1280,
+            window_height: 720,
+            fullscreen: FullscreenSetting::Windowed,
+            monitor: None,
+            fullscreen_width: None,
+            fullscreen_height: None,
+            refresh_rate: None,
+            vsync: true,
+        }
+    }
+}
+
+impl DisplaySettings {
+    pub fn load(path: &Path) -> Result<DisplaySettings, RenderError> {
+        match fs::read_to_string(path) {
+            Ok(toml_string) => Self::from_toml_str(&toml_string).map_err(|error| Self::error(path, error)),
+            Err(e) => Err(Self::error(path, e.to_string())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), RenderError> {
+        let toml_string = self.to_toml_string().map_err(|error| Self::error(path, error))?;
+
+        fs::write(path, toml_string).map_err(|e| Self::error(path, e.to_string()))
+    }
+
+    pub fn from_toml_str(toml_string: &str) -> Result<DisplaySettings, String> {
+        toml::from_str(toml_string).map_err(|e| e.to_string())
+    }
+
+    pub fn to_toml_string(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|e| e.to_string())
+    }
+
+    fn error(path: &Path, error: String) -> RenderError {
+        RenderError::DisplaySettingsError { settings_path: path.to_string_lossy().to_string(), error }
+    }
+
+    pub fn get_window_position(&self) -> Option<IVec2> {
+        match (self.window_x, self.window_y) {
+            (Some(x), Some(y)) => Some(IVec2::new(x, y)),
+            _ => None,
+        }
+    }
+
+    pub fn get_window_size(&self) -> UVec2 {
+        uvec2(self.window_width, self.window_height)
+    }
+
+    pub fn get_display_mode(&self) -> DisplayMode {
+        let monitor = match &self.monitor {
+            Some(name) => MonitorSelection::Named(name.clone()),
+            None => MonitorSelection::Current,
+        };
+
+        match self.fullscreen {
+            FullscreenSetting::Windowed => DisplayMode::Windowed,
+            FullscreenSetting::Borderless => DisplayMode::BorderlessFullscreen { monitor },
+            FullscreenSetting::Fullscreen => DisplayMode::Fullscreen {
+                monitor,
+                video_mode: match (self.fullscreen_width, self.fullscreen_height) {
+                    (Some(width), Some(height)) => Some(VideoModeRequest { size: uvec2(width, height), refresh_rate: self.refresh_rate }),
+                    _ => None,
+                },
+            },
+        }
+    }
+
+    pub fn validate(&self, monitors: &[MonitorInfo]) -> DisplaySettings { // makes saved settings safe to apply to the monitors connected now
+        let mut settings = self.clone();
+
+        let monitor = match &settings.monitor {
+            Some(name) => monitors.iter().find(|monitor| monitor.name == *name),
+            None => None,
+        };
+        if monitor.is_none() {
+            settings.monitor = None; // unplugged since the settings were saved
+        }
+
+        if let Some(monitor) = monitor.or(monitors.first()) {
+            if let (Some(width), Some(height)) = (settings.fullscreen_width, settings.fullscreen_height) {
+                let request = VideoModeRequest { size: uvec2(width, height), refresh_rate: settings.refresh_rate };
+
+                match find_closest_video_mode(&monitor.video_modes, &request) {
+                    Some(mode) => {
+                        settings.fullscreen_width = Some(mode.size.x);
+                        settings.fullscreen_height = Some(mode.size.y);
+                        settings.refresh_rate = settings.refresh_rate.map(|_| mode.refresh_rate);
+                    },
+                    None => {
+                        settings.fullscreen_width = None;
+                        settings.fullscreen_height = None;
+                        settings.refresh_rate = None;
+                    },
+                }
+            }
+        }
+
+        let max_size = monitors.iter()
+            .map(|monitor| monitor.work_area_size)
+            .fold(uvec2(0, 0), |max, size| uvec2(max.x.max(size.x), max.y.max(size.y)));
+        if max_size.x > 0 && max_size.y > 0 {
+            settings.window_width = settings.window_width.clamp(MIN_WINDOW_SIZE.min(max_size.x), max_size.x);
+            settings.window_height = settings.window_height.clamp(MIN_WINDOW_SIZE.min(max_size.y), max_size.y);
+        }
+        else {
+            settings.window_width = settings.window_width.max(MIN_WINDOW_SIZE);
+            settings.window_height = settings.window_height.max(MIN_WINDOW_SIZE);
+        }
+
+        if let Some(position) = settings.get_window_position() {
+            let visible = monitors.iter().any(|monitor| {
+                position.x >= monitor.work_area_position.x
+                    && position.y >= monitor.work_area_position.y
+                    && position.x < monitor.work_area_position.x + monitor.work_area_size.x as i32
+                    && position.y < monitor.work_area_position.y + monitor.work_area_size.y as i32
+            });
+
+            if !visible { // the title bar would be unreachable, so let the OS place it instead
+                settings.window_x = None;
+                settings.window_y = None;
+            }
+        }
+
+        settings
+    }
+}
+
+
+#[cfg(test)]
+mod display_settings_test {
+    use crate::display_settings::{DisplaySettings, FullscreenSetting};
+    use crate::monitor::{DisplayMode, MonitorInfo, MonitorSelection, VideoMode, VideoModeRequest};
+    use crate::types::{ivec2, uvec2, vec2};
+
+    fn monitor(name: &str, x: i32) -> MonitorInfo {
+        MonitorInfo {
+            index: 0,
+            name: name.to_string(),
+            position: ivec2(x, 0),
+            work_area_position: ivec2(x, 0),
+            work_area_size: uvec2(1920, 1040),
+            content_scale: vec2(1.0, 1.0),
+            current_video_mode: VideoMode { size: uvec2(1920, 1080), refresh_rate: 60, bit_depth: 24 },
+            video_modes: vec![
+                VideoMode { size: uvec2(1280, 720), refresh_rate: 60, bit_depth: 24 },
+                VideoMode { size: uvec2(1920, 1080), refresh_rate: 60, bit_depth: 24 },
+            ],
+        }
+    }
+
+    #[test]
+    fn toml_round_trip_test() {
+        let settings = DisplaySettings {
+            window_x: Some(100),
+            window_y: Some(50),
+            fullscreen: FullscreenSetting::Fullscreen,
+            monitor: Some("DELL".to_string()),
+            fullscreen_width: Some(1920),
+            fullscreen_height: Some(1080),
+            refresh_rate: Some(144),
+            ..DisplaySettings::default()
+        };
+
+        let toml_string = settings.to_toml_string().unwrap();
+
+        assert_eq!(DisplaySettings::from_toml_str(&toml_string).unwrap(), settings);
+    }
+
+    #[test]
+    fn missing_keys_use_defaults_test() {
+        let settings = DisplaySettings::from_toml_str("vsync = false\nfullscreen = \"borderless\"").unwrap();
+
+        assert!(!settings.vsync);
+        assert_eq!(settings.fullscreen, FullscreenSetting::Borderless);
+        assert_eq!(settings.window_width, DisplaySettings::default().window_width);
+    }
+
+    #[test]
+    fn display_mode_test() {
+        let settings = DisplaySettings {
+            fullscreen: FullscreenSetting::Fullscreen,
+            monitor: Some("DELL".to_string()),
+            fullscreen_width: Some(1280),
+            fullscreen_height: Some(720),
+            ..DisplaySettings::default()
+        };
+
+        assert_eq!(settings.get_display_mode(), DisplayMode::Fullscreen {
+            monitor: MonitorSelection::Named("DELL".to_string()),
+            video_mode: Some(VideoModeRequest { size: uvec2(1280, 720), refresh_rate: None }),
+        });
+    }
+
+    #[test]
+    fn validate_drops_missing_monitor_test() {
+        let settings = DisplaySettings { monitor: Some("GONE".to_string()), ..DisplaySettings::default() };
+
+        assert_eq!(settings.validate(&[monitor("DELL", 0)]).monitor, None);
+    }
+
+    #[test]
+    fn validate_picks_closest_resolution_test() {
+        let settings = DisplaySettings { fullscreen_width: Some(1366), fullscreen_height: Some(768), ..DisplaySettings::default() };
+
+        let validated = settings.validate(&[monitor("DELL", 0)]);
+
+        assert_eq!((validated.fullscreen_width, validated.fullscreen_height), (Some(1280), Some(720)));
+    }
+
+    #[test]
+    fn validate_clamps_window_test() {
+        let settings = DisplaySettings { window_x: Some(5000), window_y: Some(0), window_width: 4000, window_height: 10, ..DisplaySettings::default() };
+
+        let validated = settings.validate(&[monitor("DELL", 0), monitor("LG", 1920)]);
+
+        assert_eq!(validated.get_window_position(), None);
+        assert_eq!(validated.get_window_size(), uvec2(1920, 64));
+    }
+
+    #[test]
+    fn validate_keeps_visible_position_test() {
+        let settings = DisplaySettings { window_x: Some(2000), window_y: Some(100), ..DisplaySettings::default() };
+
+        let validated = settings.validate(&[monitor("DELL", 0), monitor("LG", 1920)]);
+
+        assert_eq!(validated.get_window_position(), Some(ivec2(2000, 100)));
+    }
+}
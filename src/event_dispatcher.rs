@@ -0,0 +1,210 @@
+use std::path::PathBuf;
+use glfw::{Action, Key, Modifiers, MouseButton};
+use crate::WindowEvent;
+use crate::types::{uvec2, vec2, UVec2, Vec2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventPropagation {
+    Continue,
+    Consume, // stops lower priority handlers from seeing the event and drops it from handle_events' output
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type KeyHandler = Box<dyn FnMut(Key, Action, Modifiers) -> EventPropagation>;
+type MouseButtonHandler = Box<dyn FnMut(MouseButton, Action, Modifiers) -> EventPropagation>;
+type FileDropHandler = Box<dyn FnMut(&[PathBuf]) -> EventPropagation>;
+
+enum EventHandler {
+    Resize(Box<dyn FnMut(UVec2) -> EventPropagation>),
+    Key(KeyHandler),
+    MouseButton(MouseButtonHandler),
+    CursorPos(Box<dyn FnMut(Vec2) -> EventPropagation>),
+    Scroll(Box<dyn FnMut(Vec2) -> EventPropagation>),
+    Focus(Box<dyn FnMut(bool) -> EventPropagation>),
+    CloseRequest(Box<dyn FnMut() -> EventPropagation>),
+    FileDrop(FileDropHandler),
+    Any(Box<dyn FnMut(&WindowEvent) -> EventPropagation>),
+}
+
+impl EventHandler {
+    fn call(&mut self, event: &WindowEvent) -> EventPropagation { // handlers only see the events they subscribed to
+        match (self, event) {
+            (EventHandler::Resize(handler), WindowEvent::Glfw(glfw::WindowEvent::FramebufferSize(width, height))) => handler(uvec2(*width as u32, *height as u32)),
+            (EventHandler::Key(handler), WindowEvent::Glfw(glfw::WindowEvent::Key(key, _, action, modifiers))) => handler(*key, *action, *modifiers),
+            (EventHandler::MouseButton(handler), WindowEvent::Glfw(glfw::WindowEvent::MouseButton(button, action, modifiers))) => handler(*button, *action, *modifiers),
+            (EventHandler::CursorPos(handler), WindowEvent::Glfw(glfw::WindowEvent::CursorPos(x, y))) => handler(vec2(*x as f32, *y as f32)),
+            (EventHandler::Scroll(handler), WindowEvent::Glfw(glfw::WindowEvent::Scroll(x, y))) => handler(vec2(*x as f32, *y as f32)),
+            (EventHandler::Focus(handler), WindowEvent::Glfw(glfw::WindowEvent::Focus(focused))) => handler(*focused),
+            (EventHandler::CloseRequest(handler), WindowEvent::Glfw(glfw::WindowEvent::Close)) => handler(),
+            (EventHandler::FileDrop(handler), WindowEvent::Glfw(glfw::WindowEvent::FileDrop(paths))) => handler(paths),
+            (EventHandler::Any(handler), event) => handler(event),
+            _ => EventPropagation::Continue,
+        }
+    }
+}
+
+struct Subscription {
+    id: SubscriptionId,
+    priority: i32,
+    handler: EventHandler,
+}
+
+pub struct EventDispatcher {
+    subscriptions: Vec<Subscription>, // kept sorted, highest priority first
+    next_id: u64,
+}
+
+impl Default for EventDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventDispatcher {
+    pub fn new() -> EventDispatcher {
+        EventDispatcher { subscriptions: vec![], next_id: 0 }
+    }
+
+    fn subscribe(&mut self, priority: i32, handler: EventHandler) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+
+        let index = self.subscriptions.partition_point(|subscription| subscription.priority >= priority); // equal priorities run in the order they subscribed
+        self.subscriptions.insert(index, Subscription { id, priority, handler });
+
+        id
+    }
+
+    pub fn on_resize<F: FnMut(UVec2) -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::Resize(Box::new(handler)))
+    }
+
+    pub fn on_key<F: FnMut(Key, Action, Modifiers) -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::Key(Box::new(handler)))
+    }
+
+    pub fn on_mouse_button<F: FnMut(MouseButton, Action, Modifiers) -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::MouseButton(Box::new(handler)))
+    }
+
+    pub fn on_cursor_pos<F: FnMut(Vec2) -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::CursorPos(Box::new(handler)))
+    }
+
+    pub fn on_scroll<F: FnMut(Vec2) -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::Scroll(Box::new(handler)))
+    }
+
+    pub fn on_focus<F: FnMut(bool) -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::Focus(Box::new(handler)))
+    }
+
+    pub fn on_close_request<F: FnMut() -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::CloseRequest(Box::new(handler)))
+    }
+
+    pub fn on_file_drop<F: FnMut(&[PathBuf]) -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::FileDrop(Box::new(handler)))
+    }
+
+    pub fn on_event<F: FnMut(&WindowEvent) -> EventPropagation + 'static>(&mut self, priority: i32, handler: F) -> SubscriptionId {
+        self.subscribe(priority, EventHandler::Any(Box::new(handler)))
+    }
+
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let count = self.subscriptions.len();
+        self.subscriptions.retain(|subscription| subscription.id != id);
+
+        self.subscriptions.len() != count
+    }
+
+    pub fn dispatch(&mut self, event: &WindowEvent) -> EventPropagation {
+        for subscription in self.subscriptions.iter_mut() {
+            if subscription.handler.call(event) == EventPropagation::Consume {
+                return EventPropagation::Consume;
+            }
+        }
+
+        EventPropagation::Continue
+    }
+}
+
+
+#[cfg(test)]
+mod event_dispatcher_tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use glfw::{Action, Key, Modifiers};
+    use crate::event_dispatcher::{EventDispatcher, EventPropagation};
+    use crate::types::uvec2;
+    use crate::WindowEvent;
+
+    fn key_event(key: Key) -> WindowEvent {
+        WindowEvent::Glfw(glfw::WindowEvent::Key(key, 0, Action::Press, Modifiers::empty()))
+    }
+
+    #[test]
+    fn dispatches_to_matching_handlers_only_test() {
+        let keys = Rc::new(RefCell::new(vec![]));
+        let sizes = Rc::new(RefCell::new(vec![]));
+        let mut dispatcher = EventDispatcher::new();
+
+        let keys_handle = keys.clone();
+        dispatcher.on_key(0, move |key, _, _| { keys_handle.borrow_mut().push(key); EventPropagation::Continue });
+        let sizes_handle = sizes.clone();
+        dispatcher.on_resize(0, move |size| { sizes_handle.borrow_mut().push(size); EventPropagation::Continue });
+
+        dispatcher.dispatch(&key_event(Key::A));
+        dispatcher.dispatch(&WindowEvent::Glfw(glfw::WindowEvent::FramebufferSize(640, 480)));
+
+        assert_eq!(*keys.borrow(), vec![Key::A]);
+        assert_eq!(*sizes.borrow(), vec![uvec2(640, 480)]);
+    }
+
+    #[test]
+    fn higher_priority_runs_first_test() {
+        let order = Rc::new(RefCell::new(vec![]));
+        let mut dispatcher = EventDispatcher::new();
+
+        for (priority, name) in [(0, "gameplay"), (10, "ui"), (0, "audio")] {
+            let order_handle = order.clone();
+            dispatcher.on_event(priority, move |_| { order_handle.borrow_mut().push(name); EventPropagation::Continue });
+        }
+
+        dispatcher.dispatch(&key_event(Key::A));
+
+        assert_eq!(*order.borrow(), vec!["ui", "gameplay", "audio"]);
+    }
+
+    #[test]
+    fn consume_stops_propagation_test() {
+        let gameplay_calls = Rc::new(RefCell::new(0));
+        let mut dispatcher = EventDispatcher::new();
+
+        dispatcher.on_key(10, |key, _, _| if key == Key::Escape { EventPropagation::Consume } else { EventPropagation::Continue });
+        let calls_handle = gameplay_calls.clone();
+        dispatcher.on_key(0, move |_, _, _| { *calls_handle.borrow_mut() += 1; EventPropagation::Continue });
+
+        assert_eq!(dispatcher.dispatch(&key_event(Key::Escape)), EventPropagation::Consume);
+        assert_eq!(dispatcher.dispatch(&key_event(Key::W)), EventPropagation::Continue);
+        assert_eq!(*gameplay_calls.borrow(), 1);
+    }
+
+    #[test]
+    fn unsubscribe_test() {
+        let calls = Rc::new(RefCell::new(0));
+        let mut dispatcher = EventDispatcher::new();
+
+        let calls_handle = calls.clone();
+        let id = dispatcher.on_close_request(0, move || { *calls_handle.borrow_mut() += 1; EventPropagation::Continue });
+
+        dispatcher.dispatch(&WindowEvent::Glfw(glfw::WindowEvent::Close));
+        assert!(dispatcher.unsubscribe(id));
+        assert!(!dispatcher.unsubscribe(id));
+        dispatcher.dispatch(&WindowEvent::Glfw(glfw::WindowEvent::Close));
+
+        assert_eq!(*calls.borrow(), 1);
+    }
+}
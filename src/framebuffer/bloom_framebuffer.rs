@@ -2,7 +2,7 @@ use gl::types::{GLenum, GLfloat, GLint, GLuint};
 use crate::{RenderError, Vertex2d};
 use crate::framebuffer::simple_framebuffer::SimpleFramebuffer;
 use crate::renderable::{GlRenderable, Renderable};
-use crate::shader::{ShaderManager, ShaderProgram};
+use crate::shader::{BuiltinShader, ShaderManager};
 use crate::types::{ivec2, IVec2, vec2, UVec2};
 
 pub struct BloomFramebuffer {
@@ -214,10 +214,8 @@ impl BloomFramebuffer {
 
     fn gaussian_blur_to_outbuffer(&self, target_size: UVec2, shader_manager: &mut ShaderManager) -> Result<(), RenderError> {
 
-        let bloom_shader = shader_manager.bind("BLOOM".to_string())?;
+        let bloom_shader = shader_manager.bind_builtin(BuiltinShader::BloomBlur)?;
         bloom_shader.set_uniform("screen_size".to_string(), &vec2(target_size.x as f32, target_size.y as f32));
-        bloom_shader.set_uniform("samples".to_string(), &self.samples);
-        self.bind_buffer_textures(bloom_shader);
 
         unsafe { gl::Disable(gl::DEPTH_TEST); }
 
@@ -242,7 +240,7 @@ impl BloomFramebuffer {
             gl::BlendFunc(gl::ONE, gl::ONE);
         }
 
-        let generic_shader = shader_manager.bind("GENERIC".to_string())?;
+        let generic_shader = shader_manager.bind_builtin(BuiltinShader::FullscreenBlit)?;
         self.ping_pong_buffers[0].bind_buffer_textures(generic_shader);
         self.out_buffer.bind_draw_target();
 
@@ -254,17 +252,4 @@ impl BloomFramebuffer {
 
         Ok(())
     }
-
-     fn bind_buffer_textures(&self, shader_program: &mut Box<dyn ShaderProgram>) { // The textures will ALWAYS be bound to unit 0 and 1
-        unsafe {
-            gl::ActiveTexture(gl::TEXTURE0);
-            gl::BindTexture(gl::TEXTURE_2D_MULTISAMPLE, self.texture_id);
-
-            gl::ActiveTexture(gl::TEXTURE1);
-            gl::BindTexture(gl::TEXTURE_2D_MULTISAMPLE, self.bloom_texture_id);
-
-            shader_program.set_uniform("colour_texture".to_string(), &0);
-            shader_program.set_uniform("bloom_texture".to_string(), &1);
-        }
-    }
 }
@@ -149,7 +149,9 @@ impl SimpleFramebuffer {
             gl::BindTexture(gl::TEXTURE_2D, self.depth_buffer_id);
 
             shader_program.set_uniform("framebuffer_texture".to_string(), &0);
-            shader_program.set_uniform("depth_buffer_texture".to_string(), &1);
+            if shader_program.get_reflection().is_none_or(|reflection| reflection.get_uniform("depth_buffer_texture").is_some()) { // the built-in blit and blur programs only read the colour
+                shader_program.set_uniform("depth_buffer_texture".to_string(), &1);
+            }
         }
     }
 
@@ -3,15 +3,20 @@ extern crate gl;
 use std::cell::RefCell;
 use std::rc::Rc;
 #[allow(unused_imports)]
-use glfw::{Action, Context, Key, ffi::glfwSetInputMode};
+use glfw::{Action, Context, Key, CursorMode, MouseButton};
 
-use crate::{GLWindow, RenderError};
+use crate::{CursorShape, CustomCursor, DisplayMode, DisplaySettings, FullscreenSetting, GLWindow, MonitorInfo, RenderError, WindowEvent};
+use crate::timing::{FrameClock, SystemClock};
+use crate::event_dispatcher::EventDispatcher;
+use crate::platform::Window;
+use crate::types::{UVec2, Vec2};
 type Result<T> = std::result::Result<T, RenderError>;
 
 
 pub struct GLHandler {
     glfw_instance: glfw::Glfw,
     glfw_window: GLWindow,
+    frame_clock: FrameClock,
 
     vsync: bool,
 }
@@ -41,7 +46,7 @@ impl GLHandler {
 
         glfw.set_swap_interval(glfw::SwapInterval::Sync(if vsync {1} else {0}));
 
-        window.set_fullscreen_mode(fullscreen, &mut glfw);
+        window.set_fullscreen_mode(fullscreen, &mut glfw)?;
 
         window.update_viewport();
         window.set_clear_color(0.1, 0.2, 0.4);
@@ -54,22 +59,67 @@ impl GLHandler {
         Ok (Rc::new(RefCell::new(GLHandler {
             glfw_instance: glfw,
             glfw_window: window,
+            frame_clock: FrameClock::new(Box::new(SystemClock::new())),
 
             vsync,
         })))
     }
 
 
+    pub fn from_display_settings (window_name: &str, display_settings: &DisplaySettings) -> Result<Rc<RefCell<GLHandler>>> {
+        let size = display_settings.get_window_size();
+        let handler = Self::new(window_name, size.x, size.y, false, display_settings.vsync)?;
+
+        {
+            let mut gl_handler = handler.borrow_mut();
+            let monitors = gl_handler.get_monitors();
+            let settings = display_settings.validate(&monitors); // monitors may have been unplugged or changed since the settings were saved
+
+            gl_handler.glfw_window.set_windowed_geometry(settings.get_window_position(), settings.get_window_size());
+            gl_handler.set_display_mode(settings.get_display_mode())?;
+        }
+
+        Ok(handler)
+    }
+
+    pub fn export_display_settings (&self) -> DisplaySettings {
+        let window = &self.glfw_window;
+        let position = window.get_windowed_position();
+        let size = window.get_windowed_size();
+        let monitor = window.get_fullscreen_monitor_name().map(|name| name.to_string());
+
+        let (fullscreen, video_mode) = match window.get_display_mode() {
+            DisplayMode::Windowed => (FullscreenSetting::Windowed, None),
+            DisplayMode::Fullscreen { video_mode, .. } => (FullscreenSetting::Fullscreen, *video_mode),
+            DisplayMode::BorderlessFullscreen { .. } => (FullscreenSetting::Borderless, None),
+        };
+
+        DisplaySettings {
+            window_x: Some(position.x),
+            window_y: Some(position.y),
+            window_width: size.x,
+            window_height: size.y,
+            fullscreen,
+            monitor,
+            fullscreen_width: video_mode.map(|mode| mode.size.x),
+            fullscreen_height: video_mode.map(|mode| mode.size.y),
+            refresh_rate: video_mode.and_then(|mode| mode.refresh_rate),
+            vsync: self.vsync,
+        }
+    }
+
+
     pub fn wind_should_close (&self) -> bool {
         self.glfw_window.get_glfw_window().should_close()
     }
 
     pub fn poll_window (&mut self) {
         self.glfw_window.get_glfw_window_mut().swap_buffers();
+        self.frame_clock.tick(!self.vsync); // vsync already paces the frames, so only cap without it
         self.glfw_instance.poll_events();
     }
 
-    pub fn handle_events (&mut self) -> Vec<glfw::WindowEvent>{
+    pub fn handle_events (&mut self) -> Vec<WindowEvent>{
         self.glfw_window.reset_deltas();
         self.glfw_window.handle_events(&mut self.glfw_instance)
     }
@@ -83,8 +133,39 @@ impl GLHandler {
         self.vsync
     }
 
+    pub fn set_fullscreen(&mut self, fullscreen: bool) -> Result<()> {
+        self.glfw_window.set_fullscreen_mode(fullscreen, &mut self.glfw_instance)
+    }
+
+    pub fn set_display_mode(&mut self, display_mode: DisplayMode) -> Result<()> {
+        self.glfw_window.set_display_mode(display_mode, &mut self.glfw_instance)
+    }
+
+    pub fn get_monitors(&mut self) -> Vec<MonitorInfo> {
+        GLWindow::get_monitors(&mut self.glfw_instance)
+    }
+
+    pub fn get_current_monitor(&mut self) -> Result<MonitorInfo> {
+        self.glfw_window.get_current_monitor(&mut self.glfw_instance)
+    }
+
     /* GETTERS AND SETTERS */
 
+    pub fn get_frame_clock (&self) -> &FrameClock {
+        &self.frame_clock
+    }
+    pub fn get_frame_clock_mut (&mut self) -> &mut FrameClock {
+        &mut self.frame_clock
+    }
+
+    pub fn get_event_dispatcher_mut (&mut self) -> &mut EventDispatcher {
+        self.glfw_window.get_event_dispatcher_mut()
+    }
+
+    pub fn get_delta_time (&self) -> f64 {
+        self.frame_clock.get_delta_time()
+    }
+
     pub fn get_window (&self) -> &GLWindow {
         &self.glfw_window
     }
@@ -93,12 +174,120 @@ impl GLHandler {
     }
 
     pub fn set_cursor_enabled(&mut self, enabled: bool) {
-        unsafe {
-            glfwSetInputMode(
-                self.glfw_window.get_glfw_window_mut().window_ptr(),
-                glfw::ffi::GLFW_CURSOR,
-                if enabled { glfw::ffi::GLFW_CURSOR_NORMAL }  else { glfw::ffi::GLFW_CURSOR_DISABLED }
-            );
-        }
+        self.glfw_window.set_cursor_mode(if enabled { CursorMode::Normal } else { CursorMode::Disabled });
+    }
+
+    pub fn set_cursor_hidden(&mut self, hidden: bool) { // hidden over the window but still free to leave it
+        self.glfw_window.set_cursor_mode(if hidden { CursorMode::Hidden } else { CursorMode::Normal });
+    }
+
+    pub fn set_cursor_shape(&mut self, shape: CursorShape) {
+        self.glfw_window.set_cursor_shape(shape);
+    }
+
+    pub fn set_custom_cursor(&mut self, cursor: &CustomCursor) {
+        self.glfw_window.set_custom_cursor(cursor);
+    }
+
+    pub fn set_raw_mouse_motion(&mut self, enabled: bool) -> Result<()> {
+        self.glfw_window.set_raw_mouse_motion(enabled, &self.glfw_instance)
+    }
+}
+
+impl Window for GLHandler {
+    fn should_close(&self) -> bool {
+        self.wind_should_close()
+    }
+
+    fn set_should_close(&mut self, should_close: bool) {
+        self.glfw_window.set_should_close(should_close);
+    }
+
+    fn request_close(&mut self) {
+        self.glfw_window.request_close();
+    }
+
+    fn confirm_close(&mut self) {
+        self.glfw_window.confirm_close();
+    }
+
+    fn set_close_interception(&mut self, intercept_close: bool) {
+        self.glfw_window.set_close_interception(intercept_close);
+    }
+
+    fn handle_events(&mut self) -> Vec<WindowEvent> {
+        GLHandler::handle_events(self)
+    }
+
+    fn get_window_size(&self) -> UVec2 {
+        self.glfw_window.get_window_size()
+    }
+
+    fn get_logical_size(&self) -> UVec2 {
+        self.glfw_window.get_logical_size()
+    }
+
+    fn get_content_scale(&self) -> Vec2 {
+        self.glfw_window.get_content_scale()
+    }
+
+    fn has_resized_this_frame(&self) -> bool {
+        self.glfw_window.has_resized_this_frame()
+    }
+
+    fn is_focused(&self) -> bool {
+        self.glfw_window.is_focused()
+    }
+
+    fn is_iconified(&self) -> bool {
+        self.glfw_window.is_iconified()
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.glfw_window.is_maximized()
+    }
+
+    fn has_key_pressed(&self, key: Key) -> bool {
+        self.glfw_window.has_key_pressed(key)
+    }
+
+    fn has_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.glfw_window.has_mouse_button_pressed(button)
+    }
+
+    fn get_mouse_pos(&self) -> Vec2 {
+        self.glfw_window.get_mouse_pos()
+    }
+
+    fn get_mouse_pos_relative(&self) -> Vec2 {
+        self.glfw_window.get_mouse_pos_relative()
+    }
+
+    fn get_mouse_delta(&self) -> Vec2 {
+        self.glfw_window.get_mouse_delta()
+    }
+
+    fn get_mouse_delta_relative(&self) -> Vec2 {
+        self.glfw_window.get_mouse_delta_relative()
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.glfw_window.set_title(title);
+    }
+
+    fn set_cursor_mode(&mut self, cursor_mode: CursorMode) {
+        self.glfw_window.set_cursor_mode(cursor_mode);
+    }
+
+    fn get_cursor_mode(&self) -> CursorMode {
+        self.glfw_window.get_cursor_mode()
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: bool) -> Result<()> {
+        GLHandler::set_fullscreen(self, fullscreen)
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.glfw_window.is_fullscreen()
     }
 }
@@ -1,9 +1,16 @@
 #![allow(dead_code)]
 
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-use glfw::{Context, Glfw, Key, Action, GlfwReceiver};
+use glfw::{Context, Glfw, Key, Action, GlfwReceiver, MouseButton, CursorMode};
+use image::RgbaImage;
 use crate::types::{Vec2, UVec2, uvec2, ivec2, IVec2, vec2};
+use crate::monitor::{find_closest_video_mode, select_monitor, DisplayMode, MonitorInfo, MonitorSelection};
+use crate::window_event::WindowEvent;
+use crate::cursor::{CursorShape, CustomCursor};
+use crate::event_dispatcher::{EventDispatcher, EventPropagation};
+use crate::window_shortcuts::{ShortcutAction, WindowShortcuts};
 
 use crate::RenderError;
 type Result<T> = std::result::Result<T, RenderError>;
@@ -16,18 +23,34 @@ type Result<T> = std::result::Result<T, RenderError>;
 pub struct GLWindow {
     glfw_window: glfw::PWindow,
     events: GlfwReceiver<(f64, glfw::WindowEvent)>,
-    window_size: UVec2, 
+    window_size: UVec2, // windowed geometry in screen coordinates, restored when leaving fullscreen
     window_pos: IVec2,
+    logical_size: UVec2, // current client area in screen coordinates, which is what the cursor is reported in
+    framebuffer_size: UVec2, // current client area in pixels
+    content_scale: Vec2,
     fullscreen_size: UVec2, 
     fullscreen_pos: IVec2,
     fullscreen: bool,
+    display_mode: DisplayMode,
+    fullscreen_monitor: Option<String>, // name of the monitor the window went fullscreen on
     has_resized_this_frame: bool,
     clear_colour: [f32; 3], 
     mouse_pos: Vec2, 
     mouse_pos_relative: Vec2,
     mouse_delta: Vec2,
     mouse_delta_relative: Vec2,
-    keys_pressed: HashSet<Key>
+    keys_pressed: HashSet<Key>,
+    mouse_buttons_pressed: HashSet<MouseButton>,
+    dropped_files: Vec<PathBuf>, // files dropped onto the window this frame
+    shortcuts: WindowShortcuts,
+    event_dispatcher: EventDispatcher,
+    intercept_close: bool, // turns close requests into CloseRequested events that have to be confirmed
+    focused: bool,
+    iconified: bool,
+    maximized: bool,
+    release_cursor_on_focus_loss: bool,
+    released_cursor_mode: Option<CursorMode>, // the mode to restore once focus comes back
+    pending_events: Vec<WindowEvent> // events raised outside of GLFW's queue, e.g. by set_display_mode
 }
 
 impl GLWindow {
@@ -50,11 +73,21 @@ impl GLWindow {
         window.set_scroll_polling(true);
         window.set_mouse_button_polling(true);
         window.set_focus_polling(true);
+        window.set_close_polling(true);
+        window.set_iconify_polling(true);
+        window.set_maximize_polling(true);
 
         window.set_framebuffer_size_polling(true);
+        window.set_size_polling(true);
+        window.set_content_scale_polling(true);
         window.set_pos_polling(true);
+        window.set_drag_and_drop_polling(true);
 
         let (window_pos_x, window_pos_y) = window.get_pos(); 
+        let (logical_width, logical_height) = window.get_size();
+        let (framebuffer_width, framebuffer_height) = window.get_framebuffer_size();
+        let (content_scale_x, content_scale_y) = window.get_content_scale();
+        let (focused, iconified, maximized) = (window.is_focused(), window.is_iconified(), window.is_maximized());
 
 
         Ok(GLWindow{ 
@@ -62,32 +95,53 @@ impl GLWindow {
             events,
             window_size: uvec2(window_width, window_height),
             window_pos: ivec2(window_pos_x, window_pos_y),
+            logical_size: uvec2(logical_width as u32, logical_height as u32),
+            framebuffer_size: uvec2(framebuffer_width as u32, framebuffer_height as u32),
+            content_scale: vec2(content_scale_x, content_scale_y),
             fullscreen_size: uvec2(0, 0),
             fullscreen_pos: ivec2(0, 0),
             fullscreen: false,
+            display_mode: DisplayMode::Windowed,
+            fullscreen_monitor: None,
             has_resized_this_frame: true,
             clear_colour: [0.0, 0.0, 0.0], 
             mouse_pos: vec2(0.0, 0.0), 
             mouse_pos_relative: vec2(0.0, 0.0),
             mouse_delta: vec2(0.0, 0.0), 
             mouse_delta_relative: vec2(0.0, 0.0),
-            keys_pressed: HashSet::new() 
+            keys_pressed: HashSet::new(),
+            mouse_buttons_pressed: HashSet::new(),
+            dropped_files: vec![],
+            shortcuts: WindowShortcuts::default(),
+            event_dispatcher: EventDispatcher::new(),
+            intercept_close: false,
+            focused,
+            iconified,
+            maximized,
+            release_cursor_on_focus_loss: true,
+            released_cursor_mode: None,
+            pending_events: vec![]
         })
     }
 
 
     pub fn update_viewport(&self) {
-        if self.fullscreen{
-            unsafe { gl::Viewport(0, 0, self.fullscreen_size.x as i32, self.fullscreen_size.y as i32); } // set the viewport size
-        }
-        else {
-            unsafe { gl::Viewport(0, 0, self.window_size.x as i32, self.window_size.y as i32); }
-        }
+        unsafe { gl::Viewport(0, 0, self.framebuffer_size.x as i32, self.framebuffer_size.y as i32); } // set the viewport size
+    }
+
+    fn refresh_sizes(&mut self) { // the size events for a mode change arrive a frame late, so ask GLFW directly
+        let (logical_width, logical_height) = self.glfw_window.get_size();
+        let (framebuffer_width, framebuffer_height) = self.glfw_window.get_framebuffer_size();
+
+        self.logical_size = uvec2(logical_width as u32, logical_height as u32);
+        self.framebuffer_size = uvec2(framebuffer_width as u32, framebuffer_height as u32);
     }
 
     
-    pub fn handle_events (&mut self, glfw: &mut Glfw) -> Vec<glfw::WindowEvent>{
-        let mut events_to_return: Vec<glfw::WindowEvent> = vec![];
+    pub fn handle_events (&mut self, glfw: &mut Glfw) -> Vec<WindowEvent>{
+        let mut events_to_return: Vec<WindowEvent> = vec![];
+        self.dispatch_pending_events(&mut events_to_return);
+
         let messages = {
             let mut new_vec = Vec::new();
             for (_, event) in glfw::flush_messages(&self.events) {
@@ -97,16 +151,26 @@ impl GLWindow {
         };
 
         for event in messages{ //handle gl events
+            let consumed = self.event_dispatcher.dispatch(&WindowEvent::Glfw(event.clone())) == EventPropagation::Consume; // subscribers get first look so UI can block input
+
             match event {
                 glfw::WindowEvent::FramebufferSize(width, height) => {
-                    if self.fullscreen { 
-                        self.fullscreen_size = uvec2(width as u32, height as u32);
+                    self.framebuffer_size = uvec2(width as u32, height as u32);
+                    self.update_viewport(); // change the window framebuffer size to new window/screen size
+                    self.has_resized_this_frame = true; 
+                }
+                glfw::WindowEvent::Size(width, height) => {
+                    self.logical_size = uvec2(width as u32, height as u32);
+                    if self.fullscreen {
+                        self.fullscreen_size = self.logical_size;
                     }
                     else {
-                        self.window_size = uvec2(width as u32, height as u32);
+                        self.window_size = self.logical_size;
                     }
-                    self.update_viewport(); // change the window framebuffer size to new window/screen size
-                    self.has_resized_this_frame = true; 
+                }
+                glfw::WindowEvent::ContentScale(x_scale, y_scale) => {
+                    self.content_scale = vec2(x_scale, y_scale);
+                    self.pending_events.push(WindowEvent::ContentScaleChanged { content_scale: self.content_scale });
                 }
                 glfw::WindowEvent::Pos(x, y) => {
                     if !self.fullscreen {
@@ -116,27 +180,29 @@ impl GLWindow {
                 }
 
                 glfw::WindowEvent::CursorPos(xpos, ypos) => {
-                    let current_size = if self.fullscreen { self.fullscreen_size } else { self.window_size };
+                    let current_size = self.logical_size; // cursor positions are in screen coordinates, not pixels
                     self.mouse_delta = vec2(xpos as f32 - self.mouse_pos.x, ypos as f32 - self.mouse_pos.y);
                     self.mouse_pos = vec2(xpos as f32, ypos as f32);
 
-                    self.mouse_pos_relative = vec2(
-                        (2.0 * self.mouse_pos.x / current_size.x as f32) - 1.0,
-                        (2.0 * self.mouse_pos.y / current_size.y as f32) - 1.0
-                    );
-                    self.mouse_delta_relative = vec2(
-                        2.0 * self.mouse_delta.x / current_size.x as f32,
-                        2.0 * self.mouse_delta.y / current_size.y as f32
-                    );
-                }
-                glfw::WindowEvent::Key(Key::F11, _, Action::Press, _) => {
-                    let mode = !self.fullscreen;
-                    self.set_fullscreen_mode(mode, glfw);
-
+                    if current_size.x > 0 && current_size.y > 0 { // a minimised window has no size to be relative to
+                        self.mouse_pos_relative = vec2(
+                            (2.0 * self.mouse_pos.x / current_size.x as f32) - 1.0,
+                            (2.0 * self.mouse_pos.y / current_size.y as f32) - 1.0
+                        );
+                        self.mouse_delta_relative = vec2(
+                            2.0 * self.mouse_delta.x / current_size.x as f32,
+                            2.0 * self.mouse_delta.y / current_size.y as f32
+                        );
+                    }
                 }
-                glfw::WindowEvent::Key(key, _, Action::Press, _)  => {
-                    if !self.keys_pressed.contains(&key) {
-                        self.keys_pressed.insert(key);
+                glfw::WindowEvent::Key(key, _, Action::Press, modifiers) if !consumed => {
+                    match self.shortcuts.action_for(key, modifiers) {
+                        Some(action) => self.run_shortcut(action, glfw),
+                        None => {
+                            if !self.keys_pressed.contains(&key) {
+                                self.keys_pressed.insert(key);
+                            }
+                        }
                     }
                 }
                 glfw::WindowEvent::Key(key, _, Action::Release, _)  => {
@@ -144,42 +210,196 @@ impl GLWindow {
                         self.keys_pressed.remove(&key);
                     }
                 }
+                glfw::WindowEvent::Close => {
+                    if consumed || self.intercept_close {
+                        self.glfw_window.set_should_close(false); // GLFW has already flagged the window, so undo it
+                    }
+                    if !consumed && self.intercept_close {
+                        self.pending_events.push(WindowEvent::CloseRequested);
+                    }
+                }
+                glfw::WindowEvent::Focus(focused) => {
+                    self.focused = focused;
+                    self.update_cursor_for_focus();
+                }
+                glfw::WindowEvent::Iconify(iconified) => {
+                    self.iconified = iconified;
+                }
+                glfw::WindowEvent::Maximize(maximized) => {
+                    self.maximized = maximized;
+                }
+                glfw::WindowEvent::FileDrop(ref paths) => {
+                    self.dropped_files.extend(paths.iter().cloned());
+                }
+                glfw::WindowEvent::MouseButton(button, Action::Press, _) if !consumed => {
+                    self.mouse_buttons_pressed.insert(button);
+                }
+                glfw::WindowEvent::MouseButton(button, Action::Release, _) => {
+                    self.mouse_buttons_pressed.remove(&button);
+                }
                         
                 _ => {}
             }
 
-            events_to_return.push(event); //store events just incase the program wants to respond to something outside this handler
+            if !consumed {
+                events_to_return.push(WindowEvent::Glfw(event)); //store events just incase the program wants to respond to something outside this handler
+            }
+            self.dispatch_pending_events(&mut events_to_return);
         }
         events_to_return
         
     }
 
-    pub fn set_fullscreen_mode (&mut self, fullscreen: bool, glfw: &mut Glfw) {
+    fn dispatch_pending_events (&mut self, events_to_return: &mut Vec<WindowEvent>) {
+        for event in std::mem::take(&mut self.pending_events) {
+            if self.event_dispatcher.dispatch(&event) == EventPropagation::Continue {
+                events_to_return.push(event);
+            }
+        }
+    }
+
+    fn run_shortcut (&mut self, action: ShortcutAction, glfw: &mut Glfw) {
+        match action {
+            ShortcutAction::ToggleFullscreen => {
+                let mode = !self.fullscreen;
+                let _ = self.set_fullscreen_mode(mode, glfw); // stays in the current mode if no monitor is available
+            }
+            ShortcutAction::Screenshot => self.pending_events.push(WindowEvent::ScreenshotRequested),
+            ShortcutAction::Close => self.request_close(),
+        }
+    }
+
+    pub fn capture_screenshot (&self) -> Result<RgbaImage> { // reads back the default framebuffer, so call it before swapping buffers
+        let size = self.get_window_size();
+        let mut data = vec![0_u8; size.x as usize * size.y as usize * 4];
+
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(0, 0, size.x as i32, size.y as i32, gl::RGBA, gl::UNSIGNED_BYTE, data.as_mut_ptr().cast());
+        }
+
+        match RgbaImage::from_raw(size.x, size.y, data) {
+            Some(mut image) => {
+                image::imageops::flip_vertical_in_place(&mut image); // GL's origin is the bottom left
+                Ok(image)
+            },
+            None => Err(RenderError::BufferError { error: "Screenshot buffer was the wrong size for the window!".to_string() }),
+        }
+    }
+
+    pub fn set_fullscreen_mode (&mut self, fullscreen: bool, glfw: &mut Glfw) -> Result<()> {
+        if fullscreen {
+            if self.fullscreen { return Ok(()) }
+            self.set_display_mode(DisplayMode::Fullscreen { monitor: MonitorSelection::Current, video_mode: None }, glfw)
+        }
+        else {
+            self.set_display_mode(DisplayMode::Windowed, glfw)
+        }
+    }
+
+    pub fn set_display_mode (&mut self, display_mode: DisplayMode, glfw: &mut Glfw) -> Result<()> {
+        match &display_mode {
+            DisplayMode::Windowed => {
+                if self.fullscreen {
+                    self.glfw_window.set_decorated(true);
+                    self.glfw_window.set_monitor(glfw::WindowMode::Windowed, self.window_pos.x, self.window_pos.y, self.window_size.x, self.window_size.y, None);
+                }
 
-        if fullscreen && !self.fullscreen  {
-            glfw.with_connected_monitors(|_, monitors| {
-                let monitor = &monitors[0];
+                self.fullscreen = false;
+                self.fullscreen_monitor = None;
+            }
+            DisplayMode::Fullscreen { monitor, video_mode } => {
+                let monitor_info = self.find_monitor(monitor, glfw)?;
+                let mode = match video_mode {
+                    Some(request) => match find_closest_video_mode(&monitor_info.video_modes, request) {
+                        Some(mode) => mode,
+                        None => return Err(RenderError::MonitorError { error: format!("Monitor \"{}\" has no video modes available", monitor_info.name) }),
+                    },
+                    None => monitor_info.current_video_mode,
+                };
+
+                self.glfw_window.set_decorated(true);
+                Self::with_glfw_monitor(glfw, &monitor_info, |glfw_monitor| {
+                    self.glfw_window.set_monitor(glfw::WindowMode::FullScreen(glfw_monitor), monitor_info.position.x, monitor_info.position.y, mode.size.x, mode.size.y, Some(mode.refresh_rate));
+                })?;
+
+                self.fullscreen_size = mode.size;
+                self.fullscreen_pos = monitor_info.position;
+                self.fullscreen = true;
+                self.fullscreen_monitor = Some(monitor_info.name);
+            }
+            DisplayMode::BorderlessFullscreen { monitor } => {
+                let monitor_info = self.find_monitor(monitor, glfw)?;
 
-                let (fullscreen_pos_x, fullscreen_pos_y) = monitor.get_pos();
-                let monitor_mode = monitor.get_video_mode().unwrap();
+                self.glfw_window.set_decorated(false); // an undecorated window covering the monitor, so alt-tabbing doesn't change the video mode
+                self.glfw_window.set_monitor(glfw::WindowMode::Windowed, monitor_info.position.x, monitor_info.position.y, monitor_info.size().x, monitor_info.size().y, None);
 
-                self.fullscreen_size = uvec2(monitor_mode.width, monitor_mode.height);
-                self.fullscreen_pos = ivec2(fullscreen_pos_x, fullscreen_pos_y);
-                self.glfw_window.set_monitor(glfw::WindowMode::FullScreen(monitor), self.fullscreen_pos.x, self.fullscreen_pos.y, self.fullscreen_size.x, self.fullscreen_size.y, None);
+                self.fullscreen_size = monitor_info.size();
+                self.fullscreen_pos = monitor_info.position;
+                self.fullscreen = true;
+                self.fullscreen_monitor = Some(monitor_info.name);
+            }
+        }
 
-            });}
-        else if self.fullscreen {
-            self.glfw_window.set_monitor(glfw::WindowMode::Windowed, self.window_pos.x, self.window_pos.y, self.window_size.x, self.window_size.y, None);
+        if self.display_mode != display_mode {
+            self.pending_events.push(WindowEvent::FullscreenChanged { fullscreen: self.fullscreen, display_mode: display_mode.clone() });
         }
 
-        self.fullscreen = fullscreen;
+        self.display_mode = display_mode;
+        self.refresh_sizes();
         self.update_viewport();
+
+        Ok(())
+    }
+
+    pub fn get_monitors (glfw: &mut Glfw) -> Vec<MonitorInfo> {
+        glfw.with_connected_monitors(|_, monitors| {
+            monitors.iter()
+                .enumerate()
+                .filter_map(|(index, monitor)| MonitorInfo::from_glfw_monitor(index, monitor))
+                .collect()
+        })
+    }
+
+    pub fn get_current_monitor (&self, glfw: &mut Glfw) -> Result<MonitorInfo> {
+        self.find_monitor(&MonitorSelection::Current, glfw)
+    }
+
+    fn find_monitor (&self, selection: &MonitorSelection, glfw: &mut Glfw) -> Result<MonitorInfo> {
+        let monitors = Self::get_monitors(glfw);
+        if monitors.is_empty() {
+            return Err(RenderError::MonitorError { error: "No monitor is available".to_string() });
+        }
+
+        let fullscreen_monitor = match (selection, &self.fullscreen_monitor) { // while fullscreen the window rect is the monitor's, so go by name instead
+            (MonitorSelection::Current, Some(name)) => select_monitor(&monitors, &MonitorSelection::Named(name.clone()), self.window_pos, self.window_size),
+            _ => None,
+        };
+
+        match fullscreen_monitor.or_else(|| select_monitor(&monitors, selection, self.window_pos, self.window_size)) {
+            Some(monitor) => Ok(monitor.clone()),
+            None => Err(RenderError::MonitorError { error: format!("No monitor matches {:?}", selection) }),
+        }
+    }
+
+    fn with_glfw_monitor<F: FnOnce(&glfw::Monitor)> (glfw: &mut Glfw, monitor_info: &MonitorInfo, f: F) -> Result<()> {
+        glfw.with_connected_monitors(|_, monitors| {
+            match monitors.get(monitor_info.index) {
+                Some(monitor) => {
+                    f(monitor);
+                    Ok(())
+                },
+                None => Err(RenderError::MonitorError { error: format!("Monitor \"{}\" was disconnected", monitor_info.name) }),
+            }
+        })
     }
 
     pub fn reset_deltas(&mut self) {
         self.mouse_delta = vec2(0.0, 0.0);
         self.mouse_delta_relative = vec2(0.0, 0.0);
         self.has_resized_this_frame = false;
+        self.dropped_files.clear();
     }
 
     /* GETTERS AND SETTERS */
@@ -199,6 +419,44 @@ impl GLWindow {
         self.clear_colour = [red, green, blue];
     }
     
+    pub fn get_event_dispatcher_mut(&mut self) -> &mut EventDispatcher {
+        &mut self.event_dispatcher
+    }
+
+    pub fn set_shortcuts(&mut self, shortcuts: WindowShortcuts) {
+        self.shortcuts = shortcuts;
+    }
+
+    pub fn get_shortcuts(&self) -> &WindowShortcuts {
+        &self.shortcuts
+    }
+
+    pub fn set_icon(&mut self, icon_paths: &[&Path]) -> Result<()> { // several sizes can be given, the OS picks the closest
+        let mut icons = vec![];
+
+        for path in icon_paths {
+            match image::open(path) {
+                Ok(image) => icons.push(image.into_rgba8()),
+                Err(e) => return Err(RenderError::TextureError { texture_path: path.to_string_lossy().to_string(), error: e.to_string() }),
+            }
+        }
+
+        self.glfw_window.set_icon(icons);
+        Ok(())
+    }
+
+    pub fn get_clipboard_string(&self) -> Option<String> {
+        self.glfw_window.get_clipboard_string()
+    }
+
+    pub fn set_clipboard_string(&mut self, string: &str) {
+        self.glfw_window.set_clipboard_string(string);
+    }
+
+    pub fn get_dropped_files(&self) -> &[PathBuf] {
+        &self.dropped_files
+    }
+
     pub fn set_title(&mut self, title: &str) {
         self.glfw_window.set_title(title);
     }
@@ -208,12 +466,51 @@ impl GLWindow {
         self.has_resized_this_frame
     }
 
-    pub fn get_window_size(&self) -> UVec2 {
-        if self.fullscreen {
-            self.fullscreen_size
+    pub fn get_window_size(&self) -> UVec2 { // in pixels, which is what viewports and framebuffers want
+        self.framebuffer_size
+    }
+
+    pub fn get_framebuffer_size(&self) -> UVec2 {
+        self.framebuffer_size
+    }
+
+    pub fn get_logical_size(&self) -> UVec2 {
+        self.logical_size
+    }
+
+    pub fn get_content_scale(&self) -> Vec2 {
+        self.content_scale
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    pub fn get_display_mode(&self) -> &DisplayMode {
+        &self.display_mode
+    }
+
+    pub fn get_fullscreen_monitor_name(&self) -> Option<&str> {
+        self.fullscreen_monitor.as_deref()
+    }
+
+    pub fn get_windowed_position(&self) -> IVec2 {
+        self.window_pos
+    }
+
+    pub fn get_windowed_size(&self) -> UVec2 { // in screen coordinates, kept while fullscreen so it can be restored
+        self.window_size
+    }
+
+    pub fn set_windowed_geometry(&mut self, position: Option<IVec2>, size: UVec2) { // a None position leaves the window where it is
+        self.window_size = size;
+        if let Some(position) = position {
+            self.window_pos = position;
         }
-        else {
-            self.window_size
+
+        if !self.fullscreen { // otherwise it's applied when returning to windowed mode
+            self.glfw_window.set_size(size.x as i32, size.y as i32);
+            self.glfw_window.set_pos(self.window_pos.x, self.window_pos.y);
         }
     }
 
@@ -221,6 +518,17 @@ impl GLWindow {
         self.mouse_pos
     }
 
+    pub fn get_mouse_pos_physical(&self) -> Vec2 { // the cursor position in framebuffer pixels, e.g. for reading back pixels under it
+        if self.logical_size.x == 0 || self.logical_size.y == 0 {
+            return self.mouse_pos;
+        }
+
+        vec2(
+            self.mouse_pos.x * self.framebuffer_size.x as f32 / self.logical_size.x as f32,
+            self.mouse_pos.y * self.framebuffer_size.y as f32 / self.logical_size.y as f32
+        )
+    }
+
     pub fn get_mouse_pos_relative(&self) -> Vec2 {
         self.mouse_pos_relative
     }
@@ -233,7 +541,126 @@ impl GLWindow {
         self.mouse_delta_relative
     }
 
-    pub fn has_key_pressed(&mut self, key: glfw::Key) -> bool{
+    pub fn has_key_pressed(&self, key: glfw::Key) -> bool{
         self.keys_pressed.contains(&key)
     }
+
+    pub fn has_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_pressed.contains(&button)
+    }
+
+    pub fn set_cursor_mode(&mut self, cursor_mode: CursorMode) {
+        if !self.focused && self.release_cursor_on_focus_loss && cursor_mode == CursorMode::Disabled {
+            self.released_cursor_mode = Some(cursor_mode); // captured once the window is focused again
+            return;
+        }
+
+        self.released_cursor_mode = None;
+        self.glfw_window.set_cursor_mode(cursor_mode);
+    }
+
+    pub fn get_cursor_mode(&self) -> CursorMode { // reports the mode the app asked for, even while it's released for lost focus
+        self.released_cursor_mode.unwrap_or_else(|| self.glfw_window.get_cursor_mode())
+    }
+
+    pub fn set_cursor_shape(&mut self, shape: CursorShape) {
+        self.glfw_window.set_cursor(Some(glfw::Cursor::standard(shape.into())));
+    }
+
+    pub fn set_custom_cursor(&mut self, cursor: &CustomCursor) {
+        self.glfw_window.set_cursor(Some(cursor.create_glfw_cursor())); // the window owns the GLFW cursor until it's replaced
+    }
+
+    pub fn reset_cursor(&mut self) {
+        self.glfw_window.set_cursor(None);
+    }
+
+    pub fn set_raw_mouse_motion(&mut self, enabled: bool, glfw: &Glfw) -> Result<()> { // only has an effect while the cursor is disabled
+        if enabled && !glfw.supports_raw_motion() {
+            return Err(RenderError::CursorError { error: "Raw mouse motion isn't supported on this platform".to_string() });
+        }
+
+        self.glfw_window.set_raw_mouse_motion(enabled);
+        Ok(())
+    }
+
+    pub fn uses_raw_mouse_motion(&self) -> bool {
+        self.glfw_window.uses_raw_mouse_motion()
+    }
+
+    fn update_cursor_for_focus(&mut self) {
+        if !self.release_cursor_on_focus_loss {
+            return;
+        }
+
+        if !self.focused && self.glfw_window.get_cursor_mode() == CursorMode::Disabled {
+            self.released_cursor_mode = Some(CursorMode::Disabled);
+            self.glfw_window.set_cursor_mode(CursorMode::Normal); // let the user actually use the other window
+        }
+        else if self.focused {
+            if let Some(cursor_mode) = self.released_cursor_mode.take() {
+                self.glfw_window.set_cursor_mode(cursor_mode);
+            }
+        }
+    }
+
+    pub fn request_close(&mut self) { // closes straight away unless close requests are being intercepted
+        if self.intercept_close {
+            self.pending_events.push(WindowEvent::CloseRequested);
+        }
+        else {
+            self.glfw_window.set_should_close(true);
+        }
+    }
+
+    pub fn confirm_close(&mut self) {
+        self.glfw_window.set_should_close(true);
+    }
+
+    pub fn set_close_interception(&mut self, intercept_close: bool) {
+        self.intercept_close = intercept_close;
+    }
+
+    pub fn set_release_cursor_on_focus_loss(&mut self, release_cursor: bool) {
+        self.release_cursor_on_focus_loss = release_cursor;
+        if !release_cursor {
+            self.released_cursor_mode = None;
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn is_iconified(&self) -> bool {
+        self.iconified
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        self.maximized
+    }
+
+    pub fn should_render(&self) -> bool { // nothing is visible while minimised, so skip the frame
+        !self.iconified && self.framebuffer_size.x > 0 && self.framebuffer_size.y > 0
+    }
+
+    pub fn iconify(&mut self) {
+        self.glfw_window.iconify();
+    }
+
+    pub fn maximize(&mut self) {
+        self.glfw_window.maximize();
+    }
+
+    pub fn restore(&mut self) {
+        self.glfw_window.restore();
+    }
+
+    pub fn should_close(&self) -> bool {
+        self.glfw_window.should_close()
+    }
+
+    pub fn set_should_close(&mut self, should_close: bool) {
+        self.glfw_window.set_should_close(should_close);
+    }
 }
\ No newline at end of file
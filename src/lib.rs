@@ -1,6 +1,12 @@
 mod render_error;
 mod gl_window;
 mod gl_handler;
+mod monitor;
+mod display_settings;
+mod cursor;
+mod event_dispatcher;
+mod window_event;
+mod window_shortcuts;
 mod camera;
 mod ui_camera;
 mod vertex;
@@ -12,9 +18,18 @@ pub mod shader;
 pub mod types;
 pub mod math;
 pub mod framebuffer;
+pub mod buffer;
+pub mod timing;
+pub mod platform;
 
 pub use gl_window::GLWindow;
 pub use gl_handler::GLHandler;
+pub use window_event::WindowEvent;
+pub use cursor::{CursorShape, CustomCursor};
+pub use event_dispatcher::{EventDispatcher, EventPropagation, SubscriptionId};
+pub use window_shortcuts::{KeyCombination, ShortcutAction, WindowShortcuts};
+pub use monitor::{DisplayMode, MonitorInfo, MonitorSelection, VideoMode, VideoModeRequest};
+pub use display_settings::{DisplaySettings, FullscreenSetting};
 pub use render_error::RenderError;
 pub use camera::Camera;
 pub use ui_camera::UICamera;
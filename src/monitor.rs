@@ -0,0 +1,207 @@
+use crate::types::{ivec2, uvec2, vec2, IVec2, UVec2, Vec2};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoMode {
+    pub size: UVec2,
+    pub refresh_rate: u32,
+    pub bit_depth: u32,
+}
+
+impl From<glfw::VidMode> for VideoMode {
+    fn from(mode: glfw::VidMode) -> Self {
+        VideoMode {
+            size: uvec2(mode.width, mode.height),
+            refresh_rate: mode.refresh_rate,
+            bit_depth: mode.red_bits + mode.green_bits + mode.blue_bits,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    pub index: usize, // position in GLFW's monitor list, the primary monitor is always 0
+    pub name: String,
+    pub position: IVec2,
+    pub work_area_position: IVec2,
+    pub work_area_size: UVec2,
+    pub content_scale: Vec2,
+    pub current_video_mode: VideoMode,
+    pub video_modes: Vec<VideoMode>,
+}
+
+impl MonitorInfo {
+    pub(crate) fn from_glfw_monitor(index: usize, monitor: &glfw::Monitor) -> Option<MonitorInfo> {
+        let current_video_mode = VideoMode::from(monitor.get_video_mode()?); // a monitor with no mode can't be used for anything
+
+        let (pos_x, pos_y) = monitor.get_pos();
+        let (work_x, work_y, work_width, work_height) = monitor.get_workarea();
+        let (scale_x, scale_y) = monitor.get_content_scale();
+
+        Some(MonitorInfo {
+            index,
+            name: monitor.get_name().unwrap_or_else(|| format!("Monitor {}", index)),
+            position: ivec2(pos_x, pos_y),
+            work_area_position: ivec2(work_x, work_y),
+            work_area_size: uvec2(work_width.max(0) as u32, work_height.max(0) as u32),
+            content_scale: vec2(scale_x, scale_y),
+            current_video_mode,
+            video_modes: monitor.get_video_modes().into_iter().map(VideoMode::from).collect(),
+        })
+    }
+
+    pub fn size(&self) -> UVec2 {
+        self.current_video_mode.size
+    }
+
+    fn overlap_area(&self, position: IVec2, size: UVec2) -> i64 {
+        let monitor_size = self.size();
+
+        let width = (self.position.x as i64 + monitor_size.x as i64).min(position.x as i64 + size.x as i64)
+            - (self.position.x as i64).max(position.x as i64);
+        let height = (self.position.y as i64 + monitor_size.y as i64).min(position.y as i64 + size.y as i64)
+            - (self.position.y as i64).max(position.y as i64);
+
+        width.max(0) * height.max(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MonitorSelection {
+    Current, // whichever monitor the window covers most of
+    Primary,
+    Named(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoModeRequest {
+    pub size: UVec2,
+    pub refresh_rate: Option<u32>, // None picks the highest rate available at that size
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayMode {
+    Windowed,
+    Fullscreen { monitor: MonitorSelection, video_mode: Option<VideoModeRequest> }, // None keeps the monitor's current mode
+    BorderlessFullscreen { monitor: MonitorSelection },
+}
+
+
+pub fn select_monitor<'a>(monitors: &'a [MonitorInfo], selection: &MonitorSelection, window_position: IVec2, window_size: UVec2) -> Option<&'a MonitorInfo> {
+    match selection {
+        MonitorSelection::Primary => monitors.first(),
+        MonitorSelection::Index(index) => monitors.get(*index),
+        MonitorSelection::Named(name) => monitors.iter().find(|monitor| monitor.name == *name),
+        MonitorSelection::Current => {
+            let best = monitors.iter().max_by_key(|monitor| monitor.overlap_area(window_position, window_size))?;
+
+            if best.overlap_area(window_position, window_size) == 0 {
+                monitors.first() // window is entirely off-screen, so fall back to the primary
+            } else {
+                Some(best)
+            }
+        }
+    }
+}
+
+pub fn find_closest_video_mode(video_modes: &[VideoMode], request: &VideoModeRequest) -> Option<VideoMode> {
+    let size_distance = |mode: &VideoMode| {
+        (mode.size.x as i64 - request.size.x as i64).abs() + (mode.size.y as i64 - request.size.y as i64).abs()
+    };
+    let refresh_distance = |mode: &VideoMode| match request.refresh_rate {
+        Some(rate) => (mode.refresh_rate as i64 - rate as i64).abs(),
+        None => -(mode.refresh_rate as i64),
+    };
+
+    video_modes.iter()
+        .min_by_key(|mode| (size_distance(mode), refresh_distance(mode), -(mode.bit_depth as i64)))
+        .copied()
+}
+
+
+#[cfg(test)]
+mod monitor_test {
+    use super::*;
+
+    fn video_mode(width: u32, height: u32, refresh_rate: u32) -> VideoMode {
+        VideoMode { size: uvec2(width, height), refresh_rate, bit_depth: 24 }
+    }
+
+    fn monitor(index: usize, name: &str, x: i32, y: i32) -> MonitorInfo {
+        MonitorInfo {
+            index,
+            name: name.to_string(),
+            position: ivec2(x, y),
+            work_area_position: ivec2(x, y),
+            work_area_size: uvec2(1920, 1040),
+            content_scale: vec2(1.0, 1.0),
+            current_video_mode: video_mode(1920, 1080, 60),
+            video_modes: vec![video_mode(1920, 1080, 60)],
+        }
+    }
+
+    #[test]
+    fn select_primary_test() {
+        let monitors = vec![monitor(0, "A", 0, 0), monitor(1, "B", 1920, 0)];
+
+        assert_eq!(select_monitor(&monitors, &MonitorSelection::Primary, ivec2(2000, 0), uvec2(100, 100)).unwrap().name, "A");
+    }
+
+    #[test]
+    fn select_named_test() {
+        let monitors = vec![monitor(0, "A", 0, 0), monitor(1, "B", 1920, 0)];
+
+        assert_eq!(select_monitor(&monitors, &MonitorSelection::Named("B".to_string()), ivec2(0, 0), uvec2(100, 100)).unwrap().index, 1);
+        assert!(select_monitor(&monitors, &MonitorSelection::Named("C".to_string()), ivec2(0, 0), uvec2(100, 100)).is_none());
+    }
+
+    #[test]
+    fn select_current_uses_largest_overlap_test() {
+        let monitors = vec![monitor(0, "A", 0, 0), monitor(1, "B", 1920, 0)];
+
+        let selected = select_monitor(&monitors, &MonitorSelection::Current, ivec2(1800, 100), uvec2(800, 600)).unwrap();
+
+        assert_eq!(selected.name, "B");
+    }
+
+    #[test]
+    fn select_current_off_screen_falls_back_to_primary_test() {
+        let monitors = vec![monitor(0, "A", 0, 0), monitor(1, "B", 1920, 0)];
+
+        let selected = select_monitor(&monitors, &MonitorSelection::Current, ivec2(-5000, -5000), uvec2(800, 600)).unwrap();
+
+        assert_eq!(selected.name, "A");
+    }
+
+    #[test]
+    fn select_with_no_monitors_test() {
+        assert!(select_monitor(&[], &MonitorSelection::Current, ivec2(0, 0), uvec2(800, 600)).is_none());
+    }
+
+    #[test]
+    fn closest_video_mode_exact_test() {
+        let modes = vec![video_mode(1280, 720, 60), video_mode(1920, 1080, 60), video_mode(1920, 1080, 144)];
+
+        let mode = find_closest_video_mode(&modes, &VideoModeRequest { size: uvec2(1920, 1080), refresh_rate: Some(60) }).unwrap();
+
+        assert_eq!(mode, video_mode(1920, 1080, 60));
+    }
+
+    #[test]
+    fn closest_video_mode_highest_refresh_test() {
+        let modes = vec![video_mode(1920, 1080, 60), video_mode(1920, 1080, 144), video_mode(2560, 1440, 165)];
+
+        let mode = find_closest_video_mode(&modes, &VideoModeRequest { size: uvec2(1920, 1080), refresh_rate: None }).unwrap();
+
+        assert_eq!(mode, video_mode(1920, 1080, 144));
+    }
+
+    #[test]
+    fn closest_video_mode_nearest_size_test() {
+        let modes = vec![video_mode(1280, 720, 60), video_mode(1920, 1080, 60)];
+
+        let mode = find_closest_video_mode(&modes, &VideoModeRequest { size: uvec2(1366, 768), refresh_rate: Some(60) }).unwrap();
+
+        assert_eq!(mode, video_mode(1280, 720, 60));
+    }
+}
@@ -0,0 +1,5 @@
+mod window;
+mod nullable_window;
+
+pub use window::Window;
+pub use nullable_window::{NullableWindow, NullableWindowState};
@@ -0,0 +1,358 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use glfw::{Action, CursorMode, Key, MouseButton};
+use crate::platform::Window;
+use crate::{DisplayMode, MonitorSelection, RenderError, WindowEvent};
+use crate::types::{uvec2, vec2, UVec2, Vec2};
+
+pub struct NullableWindowState {
+    pub queued_events: Vec<WindowEvent>, // handed out (and applied) on the next handle_events
+    pub should_close: bool,
+    pub intercept_close: bool,
+    pub size: UVec2,
+    pub content_scale: Vec2,
+    pub has_resized_this_frame: bool,
+    pub focused: bool,
+    pub iconified: bool,
+    pub maximized: bool,
+    pub keys_pressed: HashSet<Key>,
+    pub mouse_buttons_pressed: HashSet<MouseButton>,
+    pub mouse_pos: Vec2,
+    pub mouse_delta: Vec2,
+    pub title: String,
+    pub cursor_mode: CursorMode,
+    pub fullscreen: bool,
+    pub handle_events_count: u32,
+}
+
+impl NullableWindowState {
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            queued_events: vec![],
+            should_close: false,
+            intercept_close: false,
+            size,
+            content_scale: vec2(1.0, 1.0),
+            has_resized_this_frame: false,
+            focused: true,
+            iconified: false,
+            maximized: false,
+            keys_pressed: HashSet::new(),
+            mouse_buttons_pressed: HashSet::new(),
+            mouse_pos: vec2(0.0, 0.0),
+            mouse_delta: vec2(0.0, 0.0),
+            title: String::new(),
+            cursor_mode: CursorMode::Normal,
+            fullscreen: false,
+            handle_events_count: 0,
+        }
+    }
+}
+
+impl Default for NullableWindowState {
+    fn default() -> Self {
+        Self::new(uvec2(800, 600))
+    }
+}
+
+pub struct NullableWindow {
+    state: Rc<RefCell<NullableWindowState>>,
+}
+
+impl NullableWindow {
+    pub fn new(state: Rc<RefCell<NullableWindowState>>) -> Self {
+        Self {
+            state,
+        }
+    }
+
+    fn apply_event(state: &mut NullableWindowState, event: &WindowEvent, raised_events: &mut Vec<WindowEvent>) { // mirrors what GLWindow tracks, so input queries behave the same
+        match event {
+            WindowEvent::Glfw(glfw::WindowEvent::Key(key, _, Action::Press, _)) => { state.keys_pressed.insert(*key); },
+            WindowEvent::Glfw(glfw::WindowEvent::Key(key, _, Action::Release, _)) => { state.keys_pressed.remove(key); },
+            WindowEvent::Glfw(glfw::WindowEvent::MouseButton(button, Action::Press, _)) => { state.mouse_buttons_pressed.insert(*button); },
+            WindowEvent::Glfw(glfw::WindowEvent::MouseButton(button, Action::Release, _)) => { state.mouse_buttons_pressed.remove(button); },
+            WindowEvent::Glfw(glfw::WindowEvent::CursorPos(x, y)) => {
+                let mouse_pos = vec2(*x as f32, *y as f32);
+                state.mouse_delta = vec2(mouse_pos.x - state.mouse_pos.x, mouse_pos.y - state.mouse_pos.y);
+                state.mouse_pos = mouse_pos;
+            },
+            WindowEvent::Glfw(glfw::WindowEvent::FramebufferSize(width, height)) => {
+                state.size = uvec2(*width as u32, *height as u32);
+                state.has_resized_this_frame = true;
+            },
+            WindowEvent::Glfw(glfw::WindowEvent::Close) => match state.intercept_close {
+                true => raised_events.push(WindowEvent::CloseRequested),
+                false => state.should_close = true,
+            },
+            WindowEvent::Glfw(glfw::WindowEvent::Focus(focused)) => state.focused = *focused,
+            WindowEvent::Glfw(glfw::WindowEvent::Iconify(iconified)) => state.iconified = *iconified,
+            WindowEvent::Glfw(glfw::WindowEvent::Maximize(maximized)) => state.maximized = *maximized,
+            WindowEvent::ContentScaleChanged { content_scale } => state.content_scale = *content_scale,
+            _ => {}
+        }
+    }
+
+    fn relative(value: Vec2, size: UVec2) -> Vec2 {
+        if size.x == 0 || size.y == 0 {
+            return vec2(0.0, 0.0);
+        }
+        vec2(2.0 * value.x / size.x as f32, 2.0 * value.y / size.y as f32)
+    }
+}
+
+impl Window for NullableWindow {
+    fn should_close(&self) -> bool {
+        self.state.borrow().should_close
+    }
+
+    fn set_should_close(&mut self, should_close: bool) {
+        self.state.borrow_mut().should_close = should_close;
+    }
+
+    fn request_close(&mut self) {
+        let mut state = self.state.borrow_mut();
+        match state.intercept_close {
+            true => state.queued_events.push(WindowEvent::CloseRequested),
+            false => state.should_close = true,
+        }
+    }
+
+    fn confirm_close(&mut self) {
+        self.state.borrow_mut().should_close = true;
+    }
+
+    fn set_close_interception(&mut self, intercept_close: bool) {
+        self.state.borrow_mut().intercept_close = intercept_close;
+    }
+
+    fn handle_events(&mut self) -> Vec<WindowEvent> {
+        let mut state = self.state.borrow_mut();
+        state.handle_events_count += 1;
+        state.mouse_delta = vec2(0.0, 0.0);
+        state.has_resized_this_frame = false;
+
+        let mut events: Vec<WindowEvent> = state.queued_events.drain(..).collect();
+        let mut raised_events = vec![];
+        for event in &events {
+            Self::apply_event(&mut state, event, &mut raised_events);
+        }
+        events.extend(raised_events); // after the GLFW events, like GLWindow's pending events
+
+        events
+    }
+
+    fn get_window_size(&self) -> UVec2 {
+        self.state.borrow().size
+    }
+
+    fn get_logical_size(&self) -> UVec2 {
+        let state = self.state.borrow();
+        uvec2((state.size.x as f32 / state.content_scale.x) as u32, (state.size.y as f32 / state.content_scale.y) as u32)
+    }
+
+    fn get_content_scale(&self) -> Vec2 {
+        self.state.borrow().content_scale
+    }
+
+    fn has_resized_this_frame(&self) -> bool {
+        self.state.borrow().has_resized_this_frame
+    }
+
+    fn is_focused(&self) -> bool {
+        self.state.borrow().focused
+    }
+
+    fn is_iconified(&self) -> bool {
+        self.state.borrow().iconified
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.state.borrow().maximized
+    }
+
+    fn has_key_pressed(&self, key: Key) -> bool {
+        self.state.borrow().keys_pressed.contains(&key)
+    }
+
+    fn has_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.state.borrow().mouse_buttons_pressed.contains(&button)
+    }
+
+    fn get_mouse_pos(&self) -> Vec2 {
+        self.state.borrow().mouse_pos
+    }
+
+    fn get_mouse_pos_relative(&self) -> Vec2 {
+        let relative = Self::relative(self.get_mouse_pos(), self.get_logical_size());
+        vec2(relative.x - 1.0, relative.y - 1.0)
+    }
+
+    fn get_mouse_delta(&self) -> Vec2 {
+        self.state.borrow().mouse_delta
+    }
+
+    fn get_mouse_delta_relative(&self) -> Vec2 {
+        Self::relative(self.get_mouse_delta(), self.get_logical_size())
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.state.borrow_mut().title = title.to_string();
+    }
+
+    fn set_cursor_mode(&mut self, cursor_mode: CursorMode) {
+        self.state.borrow_mut().cursor_mode = cursor_mode;
+    }
+
+    fn get_cursor_mode(&self) -> CursorMode {
+        self.state.borrow().cursor_mode
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: bool) -> Result<(), RenderError> {
+        let mut state = self.state.borrow_mut();
+        if state.fullscreen != fullscreen {
+            state.fullscreen = fullscreen;
+            state.queued_events.push(WindowEvent::FullscreenChanged {
+                fullscreen,
+                display_mode: if fullscreen {
+                    DisplayMode::Fullscreen { monitor: MonitorSelection::Current, video_mode: None }
+                } else {
+                    DisplayMode::Windowed
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn is_fullscreen(&self) -> bool {
+        self.state.borrow().fullscreen
+    }
+}
+
+
+#[cfg(test)]
+mod nullable_window_tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use glfw::{Action, CursorMode, Key, Modifiers, MouseButton};
+    use crate::platform::{NullableWindow, NullableWindowState, Window};
+    use crate::types::{uvec2, vec2};
+    use crate::WindowEvent;
+
+    fn new_window() -> (NullableWindow, Rc<RefCell<NullableWindowState>>) {
+        let state = Rc::new(RefCell::new(NullableWindowState::new(uvec2(800, 600))));
+        (NullableWindow::new(state.clone()), state)
+    }
+
+    #[test]
+    fn handle_events_returns_injected_events_test() {
+        let (mut window, state) = new_window();
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Focus(false)));
+
+        let events = window.handle_events();
+
+        assert_eq!(events, vec![WindowEvent::Glfw(glfw::WindowEvent::Focus(false))]);
+        assert!(state.borrow().queued_events.is_empty());
+        assert_eq!(state.borrow().handle_events_count, 1);
+    }
+
+    #[test]
+    fn key_events_update_pressed_keys_test() {
+        let (mut window, state) = new_window();
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Key(Key::W, 0, Action::Press, Modifiers::empty())));
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::MouseButton(MouseButton::Button1, Action::Press, Modifiers::empty())));
+
+        window.handle_events();
+
+        assert!(window.has_key_pressed(Key::W));
+        assert!(window.has_mouse_button_pressed(MouseButton::Button1));
+
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Key(Key::W, 0, Action::Release, Modifiers::empty())));
+        window.handle_events();
+
+        assert!(!window.has_key_pressed(Key::W));
+    }
+
+    #[test]
+    fn cursor_events_update_mouse_test() {
+        let (mut window, state) = new_window();
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::CursorPos(400.0, 150.0)));
+
+        window.handle_events();
+
+        assert_eq!(window.get_mouse_pos(), vec2(400.0, 150.0));
+        assert_eq!(window.get_mouse_delta(), vec2(400.0, 150.0));
+        assert_eq!(window.get_mouse_pos_relative(), vec2(0.0, -0.5));
+
+        window.handle_events();
+
+        assert_eq!(window.get_mouse_delta(), vec2(0.0, 0.0));
+    }
+
+    #[test]
+    fn setters_are_recorded_test() {
+        let (mut window, state) = new_window();
+
+        window.set_title("Test");
+        window.set_cursor_mode(CursorMode::Disabled);
+        window.set_fullscreen(true).unwrap();
+
+        assert_eq!(state.borrow().title, "Test");
+        assert_eq!(state.borrow().cursor_mode, CursorMode::Disabled);
+        assert!(window.is_fullscreen());
+        assert!(matches!(window.handle_events()[..], [WindowEvent::FullscreenChanged { fullscreen: true, .. }]));
+    }
+
+    #[test]
+    fn close_event_sets_should_close_test() {
+        let (mut window, state) = new_window();
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Close));
+
+        window.handle_events();
+
+        assert!(window.should_close());
+    }
+
+    #[test]
+    fn lifecycle_events_update_state_test() {
+        let (mut window, state) = new_window();
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Focus(false)));
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Iconify(true)));
+
+        window.handle_events();
+
+        assert!(!window.is_focused());
+        assert!(window.is_iconified());
+    }
+
+    #[test]
+    fn intercepted_close_can_be_vetoed_or_confirmed_test() {
+        let (mut window, state) = new_window();
+        window.set_close_interception(true);
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Close));
+
+        let events = window.handle_events();
+
+        assert_eq!(events, vec![WindowEvent::Glfw(glfw::WindowEvent::Close), WindowEvent::CloseRequested]);
+        assert!(!window.should_close()); // vetoed by not confirming
+
+        window.request_close();
+        assert_eq!(window.handle_events(), vec![WindowEvent::CloseRequested]);
+        window.confirm_close();
+        assert!(window.should_close());
+    }
+
+    #[test]
+    fn close_without_interception_closes_test() {
+        let (mut window, state) = new_window();
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Close));
+        state.borrow_mut().queued_events.push(WindowEvent::Glfw(glfw::WindowEvent::Maximize(true)));
+
+        let events = window.handle_events();
+
+        assert!(!events.contains(&WindowEvent::CloseRequested));
+        assert!(window.should_close());
+        assert!(window.is_maximized());
+    }
+}
@@ -0,0 +1,33 @@
+use glfw::{CursorMode, Key, MouseButton};
+use crate::{RenderError, WindowEvent};
+use crate::types::{UVec2, Vec2};
+
+pub trait Window { // what app code needs from the platform, so it can be swapped for a NullableWindow in tests
+    fn should_close(&self) -> bool;
+    fn set_should_close(&mut self, should_close: bool);
+    fn request_close(&mut self); // closes straight away unless close requests are being intercepted
+    fn confirm_close(&mut self);
+    fn set_close_interception(&mut self, intercept_close: bool); // close requests become WindowEvent::CloseRequested, which the app can confirm or ignore
+    fn handle_events(&mut self) -> Vec<WindowEvent>;
+
+    fn get_window_size(&self) -> UVec2;
+    fn get_logical_size(&self) -> UVec2;
+    fn get_content_scale(&self) -> Vec2;
+    fn has_resized_this_frame(&self) -> bool;
+    fn is_focused(&self) -> bool;
+    fn is_iconified(&self) -> bool;
+    fn is_maximized(&self) -> bool;
+
+    fn has_key_pressed(&self, key: Key) -> bool;
+    fn has_mouse_button_pressed(&self, button: MouseButton) -> bool;
+    fn get_mouse_pos(&self) -> Vec2;
+    fn get_mouse_pos_relative(&self) -> Vec2;
+    fn get_mouse_delta(&self) -> Vec2;
+    fn get_mouse_delta_relative(&self) -> Vec2;
+
+    fn set_title(&mut self, title: &str);
+    fn set_cursor_mode(&mut self, cursor_mode: CursorMode);
+    fn get_cursor_mode(&self) -> CursorMode;
+    fn set_fullscreen(&mut self, fullscreen: bool) -> Result<(), RenderError>;
+    fn is_fullscreen(&self) -> bool;
+}
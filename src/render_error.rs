@@ -1,4 +1,5 @@
 use thiserror::Error;
+use crate::shader::{ShaderDiagnostic, ShaderStage};
 
 #[derive(Debug, Clone, Error)]
 pub enum RenderError {
@@ -14,8 +15,31 @@ pub enum RenderError {
     TextureError { texture_path: String, error: String },
     #[error("[{shader_name}({shader_type})] {error}")]
     ShaderError { shader_name: String, shader_type: String , error: String  },
+    #[error("[{shader_name}({stage})] {error}")]
+    ShaderStageError { shader_name: String, stage: ShaderStage, error: String },
+    #[error("[{shader_name}({stage})] Compilation failed:\n{report}")]
+    ShaderCompileError { shader_name: String, stage: ShaderStage, diagnostics: Vec<ShaderDiagnostic>, report: String },
+    #[error("[{shader_name}] Uniform error: {error}")]
+    UniformError { shader_name: String, error: String },
+    #[error("{shader_path}:{line}: {error}")]
+    ShaderIncludeError { shader_path: String, line: usize, error: String },
+    #[error("Failed to load {} shader(s) from {manifest_path}:\n{}", .errors.len(), join_errors(.errors))]
+    ShaderManifestError { manifest_path: String, errors: Vec<RenderError> },
+    #[error("[{material_name}] Material error: {error}")]
+    MaterialError { material_name: String, error: String },
     #[error("[{window_name}] {error}")]
     WindowError { window_name: String, error: String },
+    #[error("Cursor error: {error}")]
+    CursorError { error: String },
+    #[error("Monitor error: {error}")]
+    MonitorError { error: String },
+    #[error("Display settings error at {settings_path}: {error}")]
+    DisplaySettingsError { settings_path: String, error: String },
     #[error("{error}")]
     GLFWError { error: String }
-}
\ No newline at end of file
+}
+
+
+fn join_errors(errors: &[RenderError]) -> String { // one per line so the aggregated errors stay readable
+    errors.iter().map(|error| format!("  {}", error)).collect::<Vec<String>>().join("\n")
+}
@@ -0,0 +1,57 @@
+use crate::RenderError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawRange { // a sub-mesh, counted in indices for indexed renderables and vertices otherwise
+    pub first: u32,
+    pub count: u32,
+    pub base_vertex: i32, // added to every index, so each sub-mesh's indices can start at 0
+}
+
+impl DrawRange {
+    pub fn new(first: u32, count: u32) -> DrawRange {
+        DrawRange { first, count, base_vertex: 0 }
+    }
+
+    pub fn with_base_vertex(mut self, base_vertex: i32) -> Self {
+        self.base_vertex = base_vertex;
+        self
+    }
+
+    pub fn check_within(&self, element_count: u32) -> Result<(), RenderError> { // for indexed renderables, where the base vertex offsets the indices rather than the range
+        Self::check_span(self.first as i64, self.count, element_count)
+    }
+
+    pub fn check_vertices_within(&self, vertex_count: u32) -> Result<(), RenderError> { // for renderables without indices, where the base vertex moves the start of the range
+        Self::check_span(self.first as i64 + self.base_vertex as i64, self.count, vertex_count)
+    }
+
+    fn check_span(start: i64, count: u32, element_count: u32) -> Result<(), RenderError> {
+        let end = start + count as i64;
+        match start >= 0 && end <= element_count as i64 {
+            true => Ok(()),
+            false => Err(RenderError::RenderableError { error: format!("Draw range {}..{} is outside the {} elements of the renderable!", start, end, element_count) }),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod draw_range_tests {
+    use crate::renderable::DrawRange;
+
+    #[test]
+    fn check_within_test() {
+        assert!(DrawRange::new(0, 6).check_within(6).is_ok());
+        assert!(DrawRange::new(3, 3).with_base_vertex(4).check_within(6).is_ok()); // indices are offset, not the range
+        assert!(DrawRange::new(4, 3).check_within(6).is_err());
+        assert!(DrawRange::new(u32::MAX, 2).check_within(6).is_err());
+    }
+
+    #[test]
+    fn check_vertices_within_test() {
+        assert!(DrawRange::new(1, 3).with_base_vertex(2).check_vertices_within(6).is_ok());
+        assert!(DrawRange::new(3, 3).with_base_vertex(4).check_vertices_within(6).is_err());
+        assert!(DrawRange::new(0, 3).with_base_vertex(-1).check_vertices_within(6).is_err());
+        assert!(DrawRange::new(2, 3).with_base_vertex(-2).check_vertices_within(6).is_ok());
+    }
+}
@@ -3,19 +3,32 @@
 use std::marker::PhantomData;
 use gl::types::GLuint;
 use crate::{vertex, RenderError, Vertex};
-use crate::renderable::Renderable;
+use crate::renderable::{DrawRange, PrimitiveTopology, Renderable};
 
 pub enum GlRenderable<T: Vertex> {
-    InitialisedWithIndexing { vao: GLuint, vbo: GLuint, ibo: GLuint, index_count: i32},
-    Initialised { vao: GLuint, vbo: GLuint, vertex_count: i32,},
-    Uninitialised { _phantom: PhantomData<T>,}
+    InitialisedWithIndexing { vao: GLuint, vbo: GLuint, ibo: GLuint, index_count: i32, topology: PrimitiveTopology, submeshes: Vec<DrawRange> },
+    Initialised { vao: GLuint, vbo: GLuint, vertex_count: i32, topology: PrimitiveTopology, submeshes: Vec<DrawRange> },
+    Uninitialised { topology: PrimitiveTopology, submeshes: Vec<DrawRange>, _phantom: PhantomData<T>,}
 }
 
 
 impl<T: Vertex> GlRenderable<T> {
 
     pub fn new<F: Vertex> () -> GlRenderable<F> {
-        GlRenderable::Uninitialised { _phantom: PhantomData }
+        GlRenderable::Uninitialised { topology: PrimitiveTopology::Triangles, submeshes: vec![], _phantom: PhantomData }
+    }
+
+    pub fn with_topology(mut self, topology: PrimitiveTopology) -> Self {
+        self.set_topology(topology);
+        self
+    }
+
+    fn take_draw_settings(&mut self) -> (PrimitiveTopology, Vec<DrawRange>) {
+        match self {
+            Self::Initialised { topology, submeshes, .. }
+            | Self::InitialisedWithIndexing { topology, submeshes, .. }
+            | Self::Uninitialised { topology, submeshes, .. } => (*topology, std::mem::take(submeshes)),
+        }
     }
 }
 
@@ -40,6 +53,7 @@ impl<T: Vertex> Renderable<T> for GlRenderable<T> {
 
     fn initialise(&mut self, vertices: &Vec<T>, indices: Option<&Vec<u32>>) -> Result<(), RenderError> {
         self.uninitialise();
+        let (topology, submeshes) = (self.get_topology(), self.get_submeshes().to_vec());
 
         let mut vao = 0;
         let mut vbo = 0;
@@ -69,7 +83,7 @@ impl<T: Vertex> Renderable<T> for GlRenderable<T> {
         T::initialise_attrib_ptrs();
 
         match indices {
-            None => *self = Self::Initialised { vao, vbo, vertex_count: vertices.len() as i32 },
+            None => *self = Self::Initialised { vao, vbo, vertex_count: vertices.len() as i32, topology, submeshes },
             Some(index_data) => {
                 let mut ibo = 0;
                 unsafe {
@@ -88,7 +102,7 @@ impl<T: Vertex> Renderable<T> for GlRenderable<T> {
                     );
                 }
 
-                *self = Self::InitialisedWithIndexing { vao, vbo, ibo, index_count: index_data.len() as i32 }
+                *self = Self::InitialisedWithIndexing { vao, vbo, ibo, index_count: index_data.len() as i32, topology, submeshes }
             }
 
         }
@@ -150,23 +164,44 @@ impl<T: Vertex> Renderable<T> for GlRenderable<T> {
     }
 
     fn draw (&self) {
+        let _ = self.draw_range(DrawRange::new(0, self.get_element_count())); // the full range is always valid
+    }
+
+    fn draw_range(&self, range: DrawRange) -> Result<(), RenderError> {
         match self {
-            Self::Uninitialised { .. } => return,
-            Self::Initialised { vao, vertex_count, .. } => unsafe {
-                gl::BindVertexArray(*vao);
-                gl::DrawArrays(gl::TRIANGLES, 0, *vertex_count);
-                gl::BindVertexArray(0);
-            }
-            Self::InitialisedWithIndexing { vao, index_count, .. } => unsafe {
-                gl::BindVertexArray(*vao);
-                gl::DrawElements(gl::TRIANGLES,
-                                 *index_count,
-                                 gl::UNSIGNED_INT,
-                                 0 as *const _ );
-                gl::BindVertexArray(0);
+            Self::Initialised { .. } => range.check_vertices_within(self.get_element_count())?,
+            _ => range.check_within(self.get_element_count())?,
+        }
+        let topology = self.get_topology();
+        if range.count < topology.get_min_element_count() {
+            return Ok(());
+        }
+
+        unsafe {
+            if let PrimitiveTopology::Patches { vertices_per_patch } = topology {
+                gl::PatchParameteri(gl::PATCH_VERTICES, vertices_per_patch as i32);
             }
 
+            match self {
+                Self::Uninitialised { .. } => {},
+                Self::Initialised { vao, .. } => {
+                    gl::BindVertexArray(*vao);
+                    gl::DrawArrays(topology.to_gl(), range.first as i32 + range.base_vertex, range.count as i32); // there are no indices to offset, so the base vertex just moves the start
+                    gl::BindVertexArray(0);
+                }
+                Self::InitialisedWithIndexing { vao, .. } => {
+                    gl::BindVertexArray(*vao);
+                    gl::DrawElementsBaseVertex(topology.to_gl(),
+                                               range.count as i32,
+                                               gl::UNSIGNED_INT,
+                                               (range.first as usize * size_of::<GLuint>()) as *const _,
+                                               range.base_vertex);
+                    gl::BindVertexArray(0);
+                }
+            }
         }
+
+        Ok(())
     }
 
     fn uninitialise(&mut self) {
@@ -182,7 +217,8 @@ impl<T: Vertex> Renderable<T> for GlRenderable<T> {
                 gl::DeleteBuffers(1, [*ibo].as_ptr());
             }
         }
-        *self = Self::Uninitialised { _phantom: PhantomData }
+        let (topology, submeshes) = self.take_draw_settings();
+        *self = Self::Uninitialised { topology, submeshes, _phantom: PhantomData }
     }
 
     fn is_initialised(&self) -> bool {
@@ -191,4 +227,44 @@ impl<T: Vertex> Renderable<T> for GlRenderable<T> {
             _ => true
         }
     }
+
+    fn set_topology(&mut self, new_topology: PrimitiveTopology) {
+        match self {
+            Self::Initialised { topology, .. }
+            | Self::InitialisedWithIndexing { topology, .. }
+            | Self::Uninitialised { topology, .. } => *topology = new_topology,
+        }
+    }
+
+    fn get_topology(&self) -> PrimitiveTopology {
+        match self {
+            Self::Initialised { topology, .. }
+            | Self::InitialisedWithIndexing { topology, .. }
+            | Self::Uninitialised { topology, .. } => *topology,
+        }
+    }
+
+    fn get_element_count(&self) -> u32 {
+        match self {
+            Self::Initialised { vertex_count, .. } => *vertex_count as u32,
+            Self::InitialisedWithIndexing { index_count, .. } => *index_count as u32,
+            Self::Uninitialised { .. } => 0,
+        }
+    }
+
+    fn set_submeshes(&mut self, new_submeshes: Vec<DrawRange>) {
+        match self {
+            Self::Initialised { submeshes, .. }
+            | Self::InitialisedWithIndexing { submeshes, .. }
+            | Self::Uninitialised { submeshes, .. } => *submeshes = new_submeshes,
+        }
+    }
+
+    fn get_submeshes(&self) -> &[DrawRange] {
+        match self {
+            Self::Initialised { submeshes, .. }
+            | Self::InitialisedWithIndexing { submeshes, .. }
+            | Self::Uninitialised { submeshes, .. } => submeshes,
+        }
+    }
 }
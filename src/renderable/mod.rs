@@ -2,7 +2,12 @@
 mod renderable;
 mod gl_renderable;
 mod nullable_renderable;
+mod primitive_topology;
+mod draw_range;
 
 pub use renderable::Renderable;
 pub use gl_renderable::GlRenderable;
 pub use nullable_renderable::NullableRenderable;
+pub use primitive_topology::PrimitiveTopology;
+pub use draw_range::DrawRange;
+pub use nullable_renderable::RecordedDraw;
@@ -5,7 +5,14 @@ use std::marker::PhantomData;
 use std::ops::Add;
 use std::rc::Rc;
 use crate::{vertex, RenderError, Vertex};
-use crate::renderable::Renderable;
+use crate::renderable::{DrawRange, PrimitiveTopology, Renderable};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordedDraw {
+    pub topology: PrimitiveTopology,
+    pub range: DrawRange,
+}
+
 #[derive(PartialEq)]
 
 pub struct NullableRenderable<T: Vertex> {
@@ -13,6 +20,9 @@ pub struct NullableRenderable<T: Vertex> {
     vertices: Rc<RefCell<Vec<T>>>,
     indices: Rc<RefCell<Option<Vec<u32>>>>,
     draw_count: Rc<RefCell<u32>>,
+    draws: Rc<RefCell<Vec<RecordedDraw>>>,
+    topology: PrimitiveTopology,
+    submeshes: Vec<DrawRange>,
 
     _phantom: PhantomData<T>
 }
@@ -30,10 +40,18 @@ impl<T: Vertex> NullableRenderable<T> {
             vertices,
             indices,
             draw_count,
+            draws: Rc::new(RefCell::new(vec![])),
+            topology: PrimitiveTopology::Triangles,
+            submeshes: vec![],
 
             _phantom: PhantomData
         }
     }
+
+    pub fn with_draws(mut self, draws: Rc<RefCell<Vec<RecordedDraw>>>) -> Self { // records the topology and range of every draw
+        self.draws = draws;
+        self
+    }
 }
 
 impl<T: Vertex> Drop for NullableRenderable<T> {
@@ -81,6 +99,18 @@ impl<T: Vertex> Renderable<T> for NullableRenderable<T> {
 
     fn draw (&self) {
         *self.draw_count.borrow_mut() += 1;
+        self.draws.borrow_mut().push(RecordedDraw { topology: self.topology, range: DrawRange::new(0, self.get_element_count()) });
+    }
+
+    fn draw_range(&self, range: DrawRange) -> Result<(), RenderError> {
+        match self.indices.borrow().is_some() {
+            true => range.check_within(self.get_element_count())?,
+            false => range.check_vertices_within(self.get_element_count())?,
+        }
+
+        *self.draw_count.borrow_mut() += 1;
+        self.draws.borrow_mut().push(RecordedDraw { topology: self.topology, range });
+        Ok(())
     }
 
     fn uninitialise(&mut self) {
@@ -90,6 +120,33 @@ impl<T: Vertex> Renderable<T> for NullableRenderable<T> {
     fn is_initialised(&self) -> bool {
         self.initialised.borrow().clone()
     }
+
+    fn set_topology(&mut self, topology: PrimitiveTopology) {
+        self.topology = topology;
+    }
+
+    fn get_topology(&self) -> PrimitiveTopology {
+        self.topology
+    }
+
+    fn get_element_count(&self) -> u32 {
+        if !self.is_initialised() {
+            return 0;
+        }
+
+        match &*self.indices.borrow() {
+            Some(indices) => indices.len() as u32,
+            None => self.vertices.borrow().len() as u32,
+        }
+    }
+
+    fn set_submeshes(&mut self, submeshes: Vec<DrawRange>) {
+        self.submeshes = submeshes;
+    }
+
+    fn get_submeshes(&self) -> &[DrawRange] {
+        &self.submeshes
+    }
 }
 
 #[cfg(test)]
@@ -221,4 +278,48 @@ mod tests {
 
         assert_eq!(2, draw_counter.borrow().clone());
     }
+
+    #[test]
+    fn records_topology_and_ranges_of_draws() {
+        let draws = Rc::new(RefCell::new(vec![]));
+        let mut nullable_renderable = NullableRenderable::<Vertex2d>::new::<Vertex2d>(
+            Rc::new(RefCell::new(false)),
+            Rc::new(RefCell::new(vec![])),
+            Rc::new(RefCell::new(None)),
+            Rc::new(RefCell::new(0))
+        ).with_draws(draws.clone());
+
+        nullable_renderable.initialise(&vec![Vertex2d { x: 0.0, y: 0.0, u: 0.0, v: 0.0 }; 4], Some(&vec![0, 1, 2, 0, 1, 2, 2, 3, 0])).unwrap();
+        nullable_renderable.set_topology(PrimitiveTopology::Lines);
+        nullable_renderable.draw();
+        nullable_renderable.set_topology(PrimitiveTopology::Patches { vertices_per_patch: 3 });
+        nullable_renderable.draw_range(DrawRange::new(3, 6).with_base_vertex(1)).unwrap();
+
+        assert_eq!(*draws.borrow(), vec![
+            RecordedDraw { topology: PrimitiveTopology::Lines, range: DrawRange::new(0, 9) },
+            RecordedDraw { topology: PrimitiveTopology::Patches { vertices_per_patch: 3 }, range: DrawRange::new(3, 6).with_base_vertex(1) },
+        ]);
+        assert!(nullable_renderable.draw_range(DrawRange::new(6, 4)).is_err());
+        assert_eq!(draws.borrow().len(), 2);
+    }
+
+    #[test]
+    fn draws_submeshes_by_index() {
+        let draws = Rc::new(RefCell::new(vec![]));
+        let mut nullable_renderable = NullableRenderable::<Vertex2d>::new::<Vertex2d>(
+            Rc::new(RefCell::new(false)),
+            Rc::new(RefCell::new(vec![])),
+            Rc::new(RefCell::new(None)),
+            Rc::new(RefCell::new(0))
+        ).with_draws(draws.clone());
+
+        nullable_renderable.set_submeshes(vec![DrawRange::new(0, 3), DrawRange::new(3, 3)]);
+        nullable_renderable.initialise(&vec![Vertex2d { x: 0.0, y: 0.0, u: 0.0, v: 0.0 }; 6], None).unwrap();
+        nullable_renderable.draw_submesh(1).unwrap();
+
+        assert_eq!(draws.borrow()[0].range, DrawRange::new(3, 3));
+        assert!(nullable_renderable.draw_submesh(2).is_err());
+        assert!(nullable_renderable.draw_range(DrawRange::new(3, 3).with_base_vertex(1)).is_err()); // would read past the last vertex
+        assert!(nullable_renderable.draw_range(DrawRange::new(0, 3).with_base_vertex(-1)).is_err());
+    }
 }
@@ -0,0 +1,38 @@
+use gl::types::GLenum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrimitiveTopology {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    #[default]
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+    Patches { vertices_per_patch: u32 }, // for tessellation shaders, GL 4.0+
+}
+
+impl PrimitiveTopology {
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            PrimitiveTopology::Points => gl::POINTS,
+            PrimitiveTopology::Lines => gl::LINES,
+            PrimitiveTopology::LineStrip => gl::LINE_STRIP,
+            PrimitiveTopology::LineLoop => gl::LINE_LOOP,
+            PrimitiveTopology::Triangles => gl::TRIANGLES,
+            PrimitiveTopology::TriangleStrip => gl::TRIANGLE_STRIP,
+            PrimitiveTopology::TriangleFan => gl::TRIANGLE_FAN,
+            PrimitiveTopology::Patches { .. } => gl::PATCHES,
+        }
+    }
+
+    pub fn get_min_element_count(&self) -> u32 { // fewer than this draws nothing
+        match self {
+            PrimitiveTopology::Points => 1,
+            PrimitiveTopology::Lines | PrimitiveTopology::LineStrip | PrimitiveTopology::LineLoop => 2,
+            PrimitiveTopology::Triangles | PrimitiveTopology::TriangleStrip | PrimitiveTopology::TriangleFan => 3,
+            PrimitiveTopology::Patches { vertices_per_patch } => *vertices_per_patch,
+        }
+    }
+}
@@ -1,11 +1,27 @@
 use gl::types::GLuint;
 use crate::{RenderError, Vertex};
+use crate::renderable::{DrawRange, PrimitiveTopology};
 
 pub trait Renderable<T: Vertex> {
 
     fn initialise(&mut self, vertices: &Vec<T>, indices: Option<&Vec<u32>>) -> Result<(), RenderError>;
     fn update_data(&mut self, vertices: &Vec<T>, indices: Option<&Vec<u32>>) -> Result<(), RenderError>;
-    fn draw (&self);
+    fn draw (&self); // the whole buffer with the current topology
+    fn draw_range(&self, range: DrawRange) -> Result<(), RenderError>;
     fn uninitialise(&mut self);
     fn is_initialised(&self) -> bool;
+
+    fn set_topology(&mut self, topology: PrimitiveTopology); // kept across initialise and uninitialise
+    fn get_topology(&self) -> PrimitiveTopology;
+    fn get_element_count(&self) -> u32; // indices if indexed, vertices otherwise, 0 when uninitialised
+
+    fn set_submeshes(&mut self, submeshes: Vec<DrawRange>); // e.g. one range per material of a model sharing one buffer
+    fn get_submeshes(&self) -> &[DrawRange];
+
+    fn draw_submesh(&self, index: usize) -> Result<(), RenderError> {
+        match self.get_submeshes().get(index) {
+            Some(range) => self.draw_range(*range),
+            None => Err(RenderError::RenderableError { error: format!("Renderable has no submesh {}, it has {}!", index, self.get_submeshes().len()) }),
+        }
+    }
 }
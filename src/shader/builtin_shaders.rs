@@ -0,0 +1,97 @@
+use crate::shader::{SetUniform, ShaderStage, ShaderStages};
+use crate::types::{vec4, Mat4};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinShader { // programs shipped inside the library, registered under get_name() the first time they're needed
+    BloomBlur, // gaussian blur pass used by BloomFramebuffer, "BLOOM"
+    FullscreenBlit, // draws framebuffer_texture over the screen, "GENERIC"
+    Textured2d, // Vertex2d with projection and model matrices, a colour_texture and a tint
+    Textured3d, // the same for Vertex3d
+}
+
+impl BuiltinShader {
+    pub const ALL: [BuiltinShader; 4] = [BuiltinShader::BloomBlur, BuiltinShader::FullscreenBlit, BuiltinShader::Textured2d, BuiltinShader::Textured3d];
+
+    pub fn get_name(&self) -> &'static str { // registering your own program under the same name replaces the built-in one
+        match self {
+            BuiltinShader::BloomBlur => "BLOOM",
+            BuiltinShader::FullscreenBlit => "GENERIC",
+            BuiltinShader::Textured2d => "TEXTURED_2D",
+            BuiltinShader::Textured3d => "TEXTURED_3D",
+        }
+    }
+
+    pub fn get_stages(&self) -> ShaderStages {
+        let (vertex, fragment) = match self {
+            BuiltinShader::BloomBlur => (include_str!("builtin/fullscreen.vsh"), include_str!("builtin/bloom_blur.fsh")),
+            BuiltinShader::FullscreenBlit => (include_str!("builtin/fullscreen.vsh"), include_str!("builtin/fullscreen_blit.fsh")),
+            BuiltinShader::Textured2d => (include_str!("builtin/textured_2d.vsh"), include_str!("builtin/textured.fsh")),
+            BuiltinShader::Textured3d => (include_str!("builtin/textured_3d.vsh"), include_str!("builtin/textured.fsh")),
+        };
+
+        ShaderStages::new()
+            .with_named_source(ShaderStage::Vertex, format!("{}.vsh", self.get_name().to_lowercase()), vertex)
+            .with_named_source(ShaderStage::Fragment, format!("{}.fsh", self.get_name().to_lowercase()), fragment)
+    }
+
+    pub(crate) fn default_uniforms(&self) -> Vec<(&'static str, Box<dyn SetUniform>)> { // so a freshly registered program draws something sensible
+        match self {
+            BuiltinShader::BloomBlur | BuiltinShader::FullscreenBlit => vec![("framebuffer_texture", Box::new(0))],
+            BuiltinShader::Textured2d | BuiltinShader::Textured3d => vec![
+                ("colour_texture", Box::new(0)),
+                ("tint", Box::new(vec4(1.0, 1.0, 1.0, 1.0))),
+                ("projection", Box::new(Mat4::identity())),
+                ("model", Box::new(Mat4::identity())),
+            ],
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod builtin_shaders_tests {
+    use crate::shader::{BuiltinShader, ShaderStage, StageSource};
+
+    fn declared_uniforms(source: &str) -> Vec<String> {
+        source.lines()
+            .filter_map(|line| line.trim().strip_prefix("uniform "))
+            .filter_map(|declaration| declaration.trim_end_matches(';').split_whitespace().nth(1))
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn builtin_sources_are_embedded_test() {
+        for builtin in BuiltinShader::ALL {
+            let stages = builtin.get_stages();
+
+            assert!(stages.validate().is_ok());
+            assert!(stages.get_source_paths().is_empty()); // nothing on disk to watch
+
+            for stage in [ShaderStage::Vertex, ShaderStage::Fragment] {
+                match stages.get_stage(stage) {
+                    Some(StageSource::Source { source, .. }) => assert!(source.starts_with("#version 330 core")),
+                    source => panic!("{:?} {} stage is {:?}", builtin, stage, source),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_declared_uniform_has_a_default_test() {
+        let set_per_draw = [(BuiltinShader::BloomBlur, "screen_size"), (BuiltinShader::BloomBlur, "direction")]; // BloomFramebuffer sets these before every blur pass
+
+        for builtin in BuiltinShader::ALL {
+            let defaults: Vec<&str> = builtin.default_uniforms().into_iter().map(|(name, _)| name).collect();
+            let stages = builtin.get_stages();
+
+            for stage in [ShaderStage::Vertex, ShaderStage::Fragment] {
+                let Some(StageSource::Source { source, .. }) = stages.get_stage(stage) else { panic!("{:?} has no {} source", builtin, stage) };
+
+                for name in declared_uniforms(source) {
+                    assert!(defaults.contains(&name.as_str()) || set_per_draw.contains(&(builtin, name.as_str())), "{:?} has no default for {}", builtin, name);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::RenderError;
+use crate::shader::set_uniform::SetUniform;
+use crate::shader::ShaderReflection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryBarrier { // what the next commands will read that a dispatch may have written
+    ShaderStorage,
+    VertexAttribArray, // e.g. particles simulated in a storage buffer then drawn as a vertex buffer
+    ElementArray,
+    Uniform,
+    TextureFetch,
+    ShaderImageAccess,
+    Command, // indirect draw or dispatch arguments
+    BufferUpdate, // reading a buffer back to the CPU
+    TextureUpdate,
+    Framebuffer,
+    All,
+}
+
+impl MemoryBarrier {
+    pub fn to_gl(&self) -> gl::types::GLbitfield {
+        match self {
+            MemoryBarrier::ShaderStorage => gl::SHADER_STORAGE_BARRIER_BIT,
+            MemoryBarrier::VertexAttribArray => gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT,
+            MemoryBarrier::ElementArray => gl::ELEMENT_ARRAY_BARRIER_BIT,
+            MemoryBarrier::Uniform => gl::UNIFORM_BARRIER_BIT,
+            MemoryBarrier::TextureFetch => gl::TEXTURE_FETCH_BARRIER_BIT,
+            MemoryBarrier::ShaderImageAccess => gl::SHADER_IMAGE_ACCESS_BARRIER_BIT,
+            MemoryBarrier::Command => gl::COMMAND_BARRIER_BIT,
+            MemoryBarrier::BufferUpdate => gl::BUFFER_UPDATE_BARRIER_BIT,
+            MemoryBarrier::TextureUpdate => gl::TEXTURE_UPDATE_BARRIER_BIT,
+            MemoryBarrier::Framebuffer => gl::FRAMEBUFFER_BARRIER_BIT,
+            MemoryBarrier::All => gl::ALL_BARRIER_BITS,
+        }
+    }
+}
+
+pub fn memory_barrier(barriers: &[MemoryBarrier]) { // waits for earlier dispatches' writes to be visible to the given kinds of read
+    let bits = barriers.iter().fold(0, |bits, barrier| bits | barrier.to_gl());
+    if bits != 0 {
+        unsafe { gl::MemoryBarrier(bits); }
+    }
+}
+
+pub fn work_groups_for(invocations: [u32; 3], local_size: [u32; 3]) -> [u32; 3] { // enough groups to cover every invocation, the shader should bounds check the extras
+    [0, 1, 2].map(|axis| invocations[axis].div_ceil(local_size[axis].max(1)))
+}
+
+pub trait ComputeProgram {
+    fn bind(&self);
+    fn set_uniform(&mut self, name: String, value: &dyn SetUniform);
+    fn dispatch(&mut self, groups_x: u32, groups_y: u32, groups_z: u32); // binds the program first
+    fn memory_barrier(&mut self, barriers: &[MemoryBarrier]);
+    fn get_local_size(&self) -> [u32; 3]; // the shader's local_size_x/y/z
+
+    fn dispatch_invocations(&mut self, invocations_x: u32, invocations_y: u32, invocations_z: u32) { // e.g. one invocation per particle
+        let groups = work_groups_for([invocations_x, invocations_y, invocations_z], self.get_local_size());
+        self.dispatch(groups[0], groups[1], groups[2]);
+    }
+
+    fn get_reflection(&self) -> Option<&ShaderReflection> {
+        None
+    }
+
+    fn take_uniform_errors(&mut self) -> Vec<RenderError> {
+        vec![]
+    }
+}
@@ -0,0 +1,58 @@
+extern crate gl;
+use gl::types::GLint;
+use crate::RenderError;
+use crate::shader::compute_program::{memory_barrier, ComputeProgram, MemoryBarrier};
+use crate::shader::set_uniform::SetUniform;
+use crate::shader::{GLShaderProgram, ShaderPreprocessor, ShaderProgram, ShaderReflection, ShaderStage, ShaderStages};
+
+pub struct GLComputeProgram {
+    program: GLShaderProgram, // a program with just a compute stage, so it gets the same reflection and uniform checks
+    local_size: [u32; 3],
+}
+
+impl GLComputeProgram {
+    pub fn load_compute_program(filepath: &str, identifying_string: &str) -> Result<GLComputeProgram, RenderError> { // loads {filepath}.csh
+        Self::load_compute_program_with_preprocessor(filepath, identifying_string, &ShaderPreprocessor::new(), &[])
+    }
+
+    pub fn load_compute_program_with_preprocessor(filepath: &str, identifying_string: &str, preprocessor: &ShaderPreprocessor, defines: &[String]) -> Result<GLComputeProgram, RenderError> {
+        let stages = ShaderStages::from_base_path(filepath, &[ShaderStage::Compute]);
+        let program = GLShaderProgram::load_from_stages(identifying_string, &stages, preprocessor, defines)?;
+
+        let mut local_size: [GLint; 3] = [1, 1, 1];
+        unsafe { gl::GetProgramiv(program.get_id(), gl::COMPUTE_WORK_GROUP_SIZE, local_size.as_mut_ptr()); }
+
+        Ok(GLComputeProgram { program, local_size: local_size.map(|size| size.max(1) as u32) })
+    }
+}
+
+impl ComputeProgram for GLComputeProgram {
+    fn bind(&self) {
+        self.program.bind();
+    }
+
+    fn set_uniform(&mut self, name: String, value: &dyn SetUniform) {
+        self.program.set_uniform(name, value);
+    }
+
+    fn dispatch(&mut self, groups_x: u32, groups_y: u32, groups_z: u32) {
+        self.program.bind();
+        unsafe { gl::DispatchCompute(groups_x, groups_y, groups_z); }
+    }
+
+    fn memory_barrier(&mut self, barriers: &[MemoryBarrier]) {
+        memory_barrier(barriers);
+    }
+
+    fn get_local_size(&self) -> [u32; 3] {
+        self.local_size
+    }
+
+    fn get_reflection(&self) -> Option<&ShaderReflection> {
+        self.program.get_reflection()
+    }
+
+    fn take_uniform_errors(&mut self) -> Vec<RenderError> {
+        self.program.take_uniform_errors()
+    }
+}
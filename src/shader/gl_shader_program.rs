@@ -1,16 +1,20 @@
 extern crate gl;
-use gl::types::{GLuint, GLint};
-use std::collections::HashMap;
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use crate::shader::SetUniform;
 use crate::{RenderError};
 use crate::shader::shader_program::ShaderProgram;
-use super::Shader;
+use super::{inject_defines, parse_info_log, render_diagnostics, PreprocessedSource, ProgramBinaryCache, Shader, ShaderPreprocessor, ShaderReflection, ShaderStage, ShaderStages, StageSource};
 
 
 pub struct GLShaderProgram { // what will actually be used as a shader
     id: GLuint,
     _name: String,
-    uniforms: HashMap<String, GLint> // cache of the locations to free up some GPU time, however minimal
+    uniforms: HashMap<String, GLint>, // cache of the locations to free up some GPU time, however minimal
+    reflection: ShaderReflection,
+    uniform_errors: Vec<RenderError>,
+    reported_uniforms: HashSet<String>, // names already in uniform_errors, so a bad set_uniform every frame is only reported once
 }
 
 
@@ -22,101 +26,204 @@ impl Drop for GLShaderProgram {
 
 
 impl GLShaderProgram {
-    pub fn load_shader_program(filepath: &str, identifying_string: &str, geometry_included: bool) -> Result<GLShaderProgram, RenderError> { //returns the finalised shader struct
-        let mut shader_program = GLShaderProgram { id: 0, _name: identifying_string.to_string(), uniforms: HashMap::new() }; // create a struct ready for the final ID
+    fn empty(identifying_string: &str) -> GLShaderProgram {
+        GLShaderProgram { id: 0, _name: identifying_string.to_string(), uniforms: HashMap::new(), reflection: ShaderReflection::default(), uniform_errors: vec![], reported_uniforms: HashSet::new() }
+    }
 
-        // -- VERTEX SHADER -- //
+    fn reflect(&mut self) { // reads back what survived linking and fills the location cache with it
+        self.reflection = ShaderReflection::from_program(self.id);
 
-        let vert_path = format!("{}.vsh", filepath); //format for filename of the shader
+        for uniform in self.reflection.uniforms.iter().filter(|uniform| uniform.location >= 0) {
+            self.uniforms.insert(uniform.name.clone(), uniform.location);
+            if let Some(array_name) = uniform.name.strip_suffix("[0]") {
+                self.uniforms.insert(array_name.to_string(), uniform.location);
+            }
+        }
+    }
 
-        let vert_shader_source = match std::fs::read_to_string(vert_path.clone()) {
-            Ok(file_source) => file_source,
-            Err(e) => return Err(RenderError::ShaderError { shader_name: identifying_string.to_string(), shader_type: vert_path.to_string(), error: e.to_string() })
-        }; // loading source code for the vertex shader
+    pub(crate) fn get_id(&self) -> GLuint {
+        self.id
+    }
 
-        let vert_shader = match Shader::load_and_compile_shader(&vert_shader_source, gl::VERTEX_SHADER) {
-            Ok(shader) => shader,
-            Err(e) => return Err(RenderError::ShaderError { shader_name: identifying_string.to_string(), shader_type: "VERTEX".to_string(), error: e }),
-        }; //compile the vertex shader
+    pub(crate) fn file_stages(filepath: &str, geometry_included: bool) -> ShaderStages { // the .vsh/.fsh(/.gsh) layout load_shader_program has always used
+        let mut stages = vec![ShaderStage::Vertex, ShaderStage::Fragment];
+        if geometry_included {
+            stages.push(ShaderStage::Geometry);
+        }
 
-        // -- FRAGMENT SHADER -- //
+        ShaderStages::from_base_path(filepath, &stages)
+    }
 
-        let frag_path = format!("{}.fsh", filepath);  //format for filename of the shader
+    pub fn get_source_paths(filepath: &str, geometry_included: bool) -> Vec<PathBuf> { // the files load_shader_program will read
+        Self::file_stages(filepath, geometry_included).get_source_paths()
+    }
 
-        let frag_shader_source = match std::fs::read_to_string(frag_path.clone()) {
-            Ok(file_source) => file_source,
-            Err(e) => return Err(RenderError::ShaderError { shader_name: identifying_string.to_string(), shader_type: frag_path.to_string(), error: e.to_string() })
-        }; // loading source code for the fragment shader
+    pub fn load_shader_program(filepath: &str, identifying_string: &str, geometry_included: bool) -> Result<GLShaderProgram, RenderError> { //returns the finalised shader struct
+        Self::load_shader_program_with_preprocessor(filepath, identifying_string, geometry_included, &ShaderPreprocessor::new())
+    }
 
-        let frag_shader = match Shader::load_and_compile_shader(&frag_shader_source, gl::FRAGMENT_SHADER) {
-            Ok(shader) => shader,
-            Err(e) => return Err(RenderError::ShaderError { shader_name: identifying_string.to_string(), shader_type: "FRAGMENT".to_string(), error: e }),
-        }; //compile the fragment shader
-        // -- GEOMETRY SHADER -- //
+    pub fn load_shader_program_with_preprocessor(filepath: &str, identifying_string: &str, geometry_included: bool, preprocessor: &ShaderPreprocessor) -> Result<GLShaderProgram, RenderError> {
+        Self::load_shader_program_with_defines(filepath, identifying_string, geometry_included, preprocessor, &[])
+    }
 
-        let geometry_shader = if geometry_included {
-            let geometry_path = format!("{}.gsh", filepath);
+    pub fn load_shader_program_with_defines(filepath: &str, identifying_string: &str, geometry_included: bool, preprocessor: &ShaderPreprocessor, defines: &[String]) -> Result<GLShaderProgram, RenderError> {
+        Self::load_from_stages(identifying_string, &Self::file_stages(filepath, geometry_included), preprocessor, defines)
+    }
 
-            let geometry_shader_source = match std::fs::read_to_string(geometry_path.clone()) {
-                Ok(file_source) => file_source,
-                Err(e) => return Err(RenderError::ShaderError { shader_name: identifying_string.to_string(), shader_type: geometry_path.to_string(), error: e.to_string() })
-            }; // loading source code for the fragment shader
+    pub fn load_from_source(identifying_string: &str, vertex_source: &str, fragment_source: &str) -> Result<GLShaderProgram, RenderError> { // e.g. with include_str!, other stages go through ShaderStages::with_source
+        let stages = ShaderStages::new()
+            .with_source(ShaderStage::Vertex, vertex_source)
+            .with_source(ShaderStage::Fragment, fragment_source);
 
-            let geometry_shader = match Shader::load_and_compile_shader(&geometry_shader_source, gl::FRAGMENT_SHADER) {
-                Ok(shader) => shader,
-                Err(e) => return Err(RenderError::ShaderError { shader_name: identifying_string.to_string(), shader_type: "GEOMETRY".to_string(), error: e }),
-            }; //compile the fragment shader
+        Self::load_from_stages(identifying_string, &stages, &ShaderPreprocessor::new(), &[])
+    }
 
-            geometry_shader
-        } else {
-            Shader::empty_shader()
+    fn read_stage_source(identifying_string: &str, stage: ShaderStage, stage_source: &StageSource, preprocessor: &ShaderPreprocessor, defines: &[String]) -> Result<PreprocessedSource, RenderError> { // reads a stage's file, resolves its #includes and adds the variant's #defines
+        let processed = match stage_source {
+            StageSource::File(path) => match std::fs::read_to_string(path) {
+                Ok(file_source) => preprocessor.process_source(path, &file_source)?,
+                Err(e) => return Err(RenderError::ShaderStageError { shader_name: identifying_string.to_string(), stage, error: format!("{}: {}", path.display(), e) }),
+            },
+            StageSource::Source { path, source } => preprocessor.process_source(path, source)?,
         };
 
-        // -- SHADER PROGRAM -- //
+        Ok(PreprocessedSource { source: inject_defines(&processed.source, defines), source_files: processed.source_files })
+    }
 
-        unsafe {
-            shader_program.id = gl::CreateProgram();
-            gl::AttachShader(shader_program.id, vert_shader.get_ID()); // attach vertex to the shader program
-            gl::AttachShader(shader_program.id, frag_shader.get_ID()); // attach fragment to the shader program
+    pub fn load_from_stages(identifying_string: &str, stages: &ShaderStages, preprocessor: &ShaderPreprocessor, defines: &[String]) -> Result<GLShaderProgram, RenderError> { // each stage is compiled as its own shader type
+        Self::load_from_stages_cached(identifying_string, stages, preprocessor, defines, None)
+    }
 
-            if geometry_included {
-                gl::AttachShader(shader_program.id, geometry_shader.get_ID());
-            } // attach geometry to the shader program!
+    pub fn load_from_stages_cached(identifying_string: &str, stages: &ShaderStages, preprocessor: &ShaderPreprocessor, defines: &[String], cache: Option<&ProgramBinaryCache>) -> Result<GLShaderProgram, RenderError> { // tries a cached binary before compiling
+        if let Err(error) = stages.validate() {
+            return Err(RenderError::ShaderError { shader_name: identifying_string.to_string(), shader_type: "PROGRAM".to_string(), error });
+        }
+
+        let mut stage_sources: Vec<(ShaderStage, PreprocessedSource)> = vec![];
+        for (stage, stage_source) in stages.get_stages() {
+            stage_sources.push((stage, Self::read_stage_source(identifying_string, stage, stage_source, preprocessor, defines)?));
+        }
+
+        let cache = cache.filter(|cache| cache.is_enabled());
+        let cache_key = cache.map(|cache| {
+            let sources: Vec<(ShaderStage, &str)> = stage_sources.iter().map(|(stage, processed)| (*stage, processed.source.as_str())).collect();
+            cache.key(&sources, defines)
+        });
+
+        if let (Some(cache), Some(key)) = (cache, cache_key) {
+            if let Some((format, binary)) = cache.load(key) {
+                match Self::link_from_binary(identifying_string, format, &binary) {
+                    Some(shader_program) => {
+                        cache.record_hit();
+                        return Ok(shader_program);
+                    },
+                    None => cache.reject(key),
+                }
+            }
+        }
+
+        let mut shaders: Vec<Shader> = vec![];
+
+        for (stage, processed) in &stage_sources {
+            match Shader::load_and_compile_shader(&processed.source, stage.to_gl()) {
+                Ok(shader) => shaders.push(shader),
+                Err(log) => {
+                    shaders.iter().for_each(|shader| shader.delete()); // don't leak the stages that did compile
+                    return Err(Self::compile_error(identifying_string, *stage, processed, &log));
+                }
+            }
+        }
 
+        let mut shader_program = GLShaderProgram::empty(identifying_string); // create a struct ready for the final ID
+
+        unsafe {
+            shader_program.id = gl::CreateProgram();
+            if cache.is_some() {
+                gl::ProgramParameteri(shader_program.id, gl::PROGRAM_BINARY_RETRIEVABLE_HINT, gl::TRUE as GLint);
+            }
+            for shader in &shaders {
+                gl::AttachShader(shader_program.id, shader.get_ID());
+            }
             gl::LinkProgram(shader_program.id);
         }
 
+        shaders.iter().for_each(|shader| shader.delete()); // flagged for deletion, they go once the program does
+
         //Get errors and handle them (this is a bit of a black box, but it works)
         unsafe {
             let mut success = 0;
             gl::GetProgramiv(shader_program.id, gl::LINK_STATUS, &mut success);
             if success == 0 {
-                let mut v: Vec<u8> = Vec::with_capacity(1024);
                 let mut log_len: i32 = 0;
-                gl::GetProgramInfoLog(
-                    shader_program.id,
-                    1024,
-                    &mut log_len,
-                    v.as_mut_ptr().cast(),
-                );
-                v.set_len(log_len.try_into().unwrap());
+                gl::GetProgramiv(shader_program.id, gl::INFO_LOG_LENGTH, &mut log_len);
+
+                let mut v: Vec<u8> = vec![0; log_len.max(1) as usize];
+                let mut written: i32 = 0;
+                gl::GetProgramInfoLog(shader_program.id, v.len() as GLsizei, &mut written, v.as_mut_ptr().cast());
+                v.truncate(written.max(0) as usize);
                 return Err(RenderError::ShaderError { shader_name: identifying_string.to_string(), shader_type: "PROGRAM".to_string(), error: format!("Program Link Error: {}", String::from_utf8_lossy(&v)) });
             }
         }
 
-        let mut num_of_uniforms = 0;
-        unsafe { gl::GetProgramiv(shader_program.id, gl::ACTIVE_UNIFORMS, &mut num_of_uniforms); }
+        if let (Some(cache), Some(key)) = (cache, cache_key) {
+            if let Some((format, binary)) = shader_program.get_binary() {
+                cache.store(key, format, &binary);
+            }
+        }
+
+        shader_program.reflect();
+        shader_program.bind();
 
-        vert_shader.delete();
-        frag_shader.delete(); //clean up now unused memory for the parts of the shaders
+        Ok(shader_program)
+    }
 
-        if geometry_included {
-            geometry_shader.delete();
+    fn compile_error(identifying_string: &str, stage: ShaderStage, processed: &PreprocessedSource, log: &str) -> RenderError { // parses the driver's log and points at the original files
+        let diagnostics = parse_info_log(log);
+        if diagnostics.is_empty() { // some drivers fail without writing a log
+            return RenderError::ShaderStageError { shader_name: identifying_string.to_string(), stage, error: log.to_string() };
+        }
+
+        let report = render_diagnostics(&diagnostics, &processed.source, &|file| processed.get_source_file(file).map(|path| path.display().to_string()));
+        RenderError::ShaderCompileError { shader_name: identifying_string.to_string(), stage, diagnostics, report }
+    }
+
+    fn link_from_binary(identifying_string: &str, format: GLenum, binary: &[u8]) -> Option<GLShaderProgram> { // None if the driver won't take it
+        let mut shader_program = GLShaderProgram::empty(identifying_string);
+        let mut success = 0;
+
+        unsafe {
+            shader_program.id = gl::CreateProgram();
+            gl::ProgramBinary(shader_program.id, format, binary.as_ptr().cast(), binary.len() as GLsizei);
+            gl::GetProgramiv(shader_program.id, gl::LINK_STATUS, &mut success);
+        }
+
+        if success == 0 {
+            return None; // dropping deletes the program
         }
 
+        shader_program.reflect();
         shader_program.bind();
 
-        Ok(shader_program)
+        Some(shader_program)
+    }
+
+    fn get_binary(&self) -> Option<(GLenum, Vec<u8>)> {
+        let mut length: GLint = 0;
+        unsafe { gl::GetProgramiv(self.id, gl::PROGRAM_BINARY_LENGTH, &mut length); }
+        if length <= 0 {
+            return None;
+        }
+
+        let mut binary: Vec<u8> = vec![0; length as usize];
+        let mut written: GLsizei = 0;
+        let mut format: GLenum = 0;
+        unsafe { gl::GetProgramBinary(self.id, length, &mut written, &mut format, binary.as_mut_ptr().cast()); }
+        binary.truncate(written.max(0) as usize);
+
+        match binary.is_empty() {
+            true => None,
+            false => Some((format, binary)),
+        }
     }
 }
 
@@ -126,6 +233,18 @@ impl ShaderProgram for GLShaderProgram {
     }
 
     fn set_uniform(&mut self, name: String, value: &dyn SetUniform){
+        if let Err(e) = self.try_set_uniform(name.clone(), value) {
+            if self.reported_uniforms.insert(name) {
+                self.uniform_errors.push(e);
+            }
+        }
+    }
+
+    fn try_set_uniform(&mut self, name: String, value: &dyn SetUniform) -> Result<(), RenderError> {
+        if let Err(error) = self.reflection.check_uniform_value(&name, value) { // never write to location -1
+            return Err(RenderError::UniformError { shader_name: self._name.clone(), error });
+        }
+
         let location: GLint;
 
         self.bind();
@@ -133,8 +252,8 @@ impl ShaderProgram for GLShaderProgram {
         match &mut self.uniforms.get(&name) { //check the uniform cache for a location associated to this name
             None => {
                 let uniform = std::ffi::CString::new(name.clone()).unwrap(); // cstring bollocks for memory security (weird, right?)
-                location = unsafe { gl::GetUniformLocation(self.id, uniform.as_ptr() as *const i8) }; // request location ID from GPU
-                
+                location = unsafe { gl::GetUniformLocation(self.id, uniform.as_ptr() as *const i8) }; // request location ID from GPU, only array elements get here now
+
                 self.uniforms.insert(name, location); //add the location ID to the cache
             },
             Some(val) => {
@@ -143,5 +262,26 @@ impl ShaderProgram for GLShaderProgram {
         };
 
         unsafe { value.set_uniform(location); }
+        Ok(())
     }
-}
\ No newline at end of file
+
+    fn get_reflection(&self) -> Option<&ShaderReflection> {
+        Some(&self.reflection)
+    }
+
+    fn take_uniform_errors(&mut self) -> Vec<RenderError> {
+        std::mem::take(&mut self.uniform_errors)
+    }
+
+    fn bind_uniform_block(&mut self, block_name: &str, binding: u32) -> Result<(), RenderError> {
+        let block = match self.reflection.uniform_blocks.iter_mut().find(|block| block.name == block_name) {
+            Some(block) => block,
+            None => return Err(RenderError::UniformError { shader_name: self._name.clone(), error: format!("{} isn't an active uniform block", block_name) }),
+        };
+
+        unsafe { gl::UniformBlockBinding(self.id, block.index, binding); }
+        block.binding = binding as GLint;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,402 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use serde::Deserialize;
+use crate::RenderError;
+use crate::shader::shader_manifest::uniform_from_toml;
+use crate::shader::{SamplerBinding, SetUniform, ShaderManager, ShaderProgram, UniformType, UniformValue};
+use crate::texture::TextureManager;
+
+// One entry of materials.toml, e.g.
+//
+// [BRICK_WALL]
+// shader = "LIT"
+// uniforms = { u_tint = [1.0, 0.9, 0.9, 1.0], u_roughness = 0.8 }
+// textures = { albedo = "brick", normal_map = { texture = "brick_normal", unit = 3 } }
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MaterialDefinition {
+    shader: String,
+    #[serde(default)]
+    uniforms: BTreeMap<String, toml::Value>,
+    #[serde(default)]
+    textures: BTreeMap<String, TextureDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum TextureDefinition {
+    Texture(String), // given the lowest free unit, in sampler name order
+    Slot { texture: String, unit: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureSlot {
+    pub texture: String, // the id in the TextureManager
+    pub unit: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    name: String, // for errors, the key in materials.toml
+    shader_name: String,
+    uniforms: BTreeMap<String, UniformValue>,
+    textures: BTreeMap<String, TextureSlot>, // keyed by the sampler uniform
+}
+
+impl Material {
+    pub fn new(name: &str, shader_name: &str) -> Material {
+        Material {
+            name: name.to_string(),
+            shader_name: shader_name.to_string(),
+            uniforms: BTreeMap::new(),
+            textures: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_uniform(mut self, name: &str, value: &dyn SetUniform) -> Self {
+        self.uniforms.insert(name.to_string(), value.to_uniform_value());
+        self
+    }
+
+    pub fn with_texture(mut self, sampler_name: &str, texture: &str, unit: u32) -> Self {
+        self.textures.insert(sampler_name.to_string(), TextureSlot { texture: texture.to_string(), unit });
+        self
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_shader_name(&self) -> &str {
+        &self.shader_name
+    }
+
+    pub fn get_uniform(&self, name: &str) -> Option<&UniformValue> {
+        self.uniforms.get(name)
+    }
+
+    pub fn get_uniforms(&self) -> &BTreeMap<String, UniformValue> {
+        &self.uniforms
+    }
+
+    pub fn get_texture(&self, sampler_name: &str) -> Option<&TextureSlot> {
+        self.textures.get(sampler_name)
+    }
+
+    pub fn get_textures(&self) -> &BTreeMap<String, TextureSlot> {
+        &self.textures
+    }
+
+    pub fn load_materials_from_assets_folder(graphics_base_path: &str) -> Result<HashMap<String, Rc<Material>>, RenderError> { // reads materials.toml next to shaders.toml and textures.toml
+        let material_definitions_file = Path::new(graphics_base_path).join("materials.toml");
+
+        match fs::read_to_string(&material_definitions_file) {
+            Ok(toml_string) => parse_materials(&toml_string),
+            Err(e) => Err(RenderError::MaterialError { material_name: material_definitions_file.display().to_string(), error: e.to_string() }),
+        }
+    }
+
+    pub fn apply(&self, state: &mut MaterialState, shader_manager: &mut ShaderManager, texture_manager: &mut TextureManager) -> Result<(), RenderError> {
+        self.apply_with(state, shader_manager, &mut |texture, unit| texture_manager.bind_to_unit(texture.to_string(), unit))
+    }
+
+    fn apply_with(&self, state: &mut MaterialState, shader_manager: &mut ShaderManager, bind_texture: &mut dyn FnMut(&str, u32) -> bool) -> Result<(), RenderError> {
+        state.apply(shader_manager, &self.name, &self.shader_name, self.uniforms.iter(), self.textures.iter(), bind_texture)
+    }
+
+    fn from_definition(name: &str, definition: MaterialDefinition) -> Result<Material, String> {
+        let mut material = Material::new(name, &definition.shader);
+
+        for (name, value) in &definition.uniforms {
+            let value = uniform_from_toml(value).map_err(|e| format!("uniform {}: {}", name, e))?;
+            material.uniforms.insert(name.clone(), value);
+        }
+
+        let mut used_units: Vec<u32> = definition.textures.values()
+            .filter_map(|texture| match texture { TextureDefinition::Slot { unit, .. } => Some(*unit), _ => None })
+            .collect();
+        for (sampler_name, texture) in definition.textures {
+            let slot = match texture {
+                TextureDefinition::Slot { texture, unit } => TextureSlot { texture, unit },
+                TextureDefinition::Texture(texture) => {
+                    let unit = (0..).find(|unit| !used_units.contains(unit)).unwrap();
+                    used_units.push(unit);
+                    TextureSlot { texture, unit }
+                },
+            };
+            material.textures.insert(sampler_name, slot);
+        }
+
+        Ok(material)
+    }
+}
+
+pub fn parse_materials(toml_string: &str) -> Result<HashMap<String, Rc<Material>>, RenderError> {
+    let table: HashMap<String, toml::Value> = toml::from_str(toml_string)
+        .map_err(|e| RenderError::MaterialError { material_name: "materials.toml".to_string(), error: e.to_string() })?;
+
+    table.into_iter()
+        .map(|(name, value)| {
+            value.try_into::<MaterialDefinition>()
+                .map_err(|e| e.to_string())
+                .and_then(|definition| Material::from_definition(&name, definition))
+                .map(|material| (name.clone(), Rc::new(material)))
+                .map_err(|error| RenderError::MaterialError { material_name: name, error })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct MaterialInstance { // shares a material, overriding some of its uniforms and textures, e.g. a per-object tint
+    material: Rc<Material>,
+    uniforms: BTreeMap<String, UniformValue>,
+    textures: BTreeMap<String, TextureSlot>,
+}
+
+impl MaterialInstance {
+    pub fn new(material: Rc<Material>) -> MaterialInstance {
+        MaterialInstance {
+            material,
+            uniforms: BTreeMap::new(),
+            textures: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_uniform(&mut self, name: &str, value: &dyn SetUniform) {
+        self.uniforms.insert(name.to_string(), value.to_uniform_value());
+    }
+
+    pub fn set_texture(&mut self, sampler_name: &str, texture: &str) { // keeps the material's unit for the sampler, or takes the next free one
+        let unit = match self.get_texture(sampler_name) {
+            Some(slot) => slot.unit,
+            None => (0..).find(|unit| !self.material.textures.values().chain(self.textures.values()).any(|slot| slot.unit == *unit)).unwrap(),
+        };
+        self.textures.insert(sampler_name.to_string(), TextureSlot { texture: texture.to_string(), unit });
+    }
+
+    pub fn clear_overrides(&mut self) {
+        self.uniforms.clear();
+        self.textures.clear();
+    }
+
+    pub fn get_material(&self) -> &Rc<Material> {
+        &self.material
+    }
+
+    pub fn get_uniform(&self, name: &str) -> Option<&UniformValue> {
+        self.uniforms.get(name).or_else(|| self.material.get_uniform(name))
+    }
+
+    pub fn get_texture(&self, sampler_name: &str) -> Option<&TextureSlot> {
+        self.textures.get(sampler_name).or_else(|| self.material.get_texture(sampler_name))
+    }
+
+    pub fn apply(&self, state: &mut MaterialState, shader_manager: &mut ShaderManager, texture_manager: &mut TextureManager) -> Result<(), RenderError> {
+        self.apply_with(state, shader_manager, &mut |texture, unit| texture_manager.bind_to_unit(texture.to_string(), unit))
+    }
+
+    fn apply_with(&self, state: &mut MaterialState, shader_manager: &mut ShaderManager, bind_texture: &mut dyn FnMut(&str, u32) -> bool) -> Result<(), RenderError> {
+        let uniforms = self.material.uniforms.iter()
+            .filter(|(name, _)| !self.uniforms.contains_key(*name))
+            .chain(self.uniforms.iter());
+        let textures = self.material.textures.iter()
+            .filter(|(name, _)| !self.textures.contains_key(*name))
+            .chain(self.textures.iter());
+
+        state.apply(shader_manager, &self.material.name, &self.material.shader_name, uniforms, textures, bind_texture)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MaterialState { // what the last applied materials left bound, so applying skips anything that's already set
+    bound_shader: Option<String>,
+    uniforms: HashMap<String, HashMap<String, UniformValue>>, // per shader, programs keep their uniforms while unbound
+    texture_units: HashMap<u32, String>,
+}
+
+impl MaterialState {
+    pub fn new() -> MaterialState {
+        MaterialState::default()
+    }
+
+    pub fn invalidate(&mut self) { // call after binding shaders or textures without a material
+        self.bound_shader = None;
+        self.uniforms.clear();
+        self.texture_units.clear();
+    }
+
+    pub fn invalidate_shader(&mut self, shader_name: &str) { // e.g. for each name returned by ShaderManager::reload_changed_shaders, a new program has none of the old uniforms
+        if self.bound_shader.as_deref() == Some(shader_name) {
+            self.bound_shader = None;
+        }
+        self.uniforms.remove(shader_name);
+    }
+
+    pub fn get_bound_shader(&self) -> Option<&str> {
+        self.bound_shader.as_deref()
+    }
+
+    fn apply<'a>(&mut self, shader_manager: &mut ShaderManager, material_name: &str, shader_name: &str,
+                 uniforms: impl Iterator<Item = (&'a String, &'a UniformValue)>,
+                 textures: impl Iterator<Item = (&'a String, &'a TextureSlot)>,
+                 bind_texture: &mut dyn FnMut(&str, u32) -> bool) -> Result<(), RenderError> { // the rest of the material is still applied if a texture is missing
+        let shader = match self.bound_shader.as_deref() == Some(shader_name) {
+            true => shader_manager.get_shader(shader_name.to_string())?,
+            false => {
+                let shader = shader_manager.bind(shader_name.to_string())?;
+                self.bound_shader = Some(shader_name.to_string());
+                shader
+            },
+        };
+        let set_uniforms = self.uniforms.entry(shader_name.to_string()).or_default();
+        let mut texture_error = None;
+
+        for (sampler_name, slot) in textures {
+            if self.texture_units.get(&slot.unit) != Some(&slot.texture) {
+                match bind_texture(&slot.texture, slot.unit) {
+                    true => { self.texture_units.insert(slot.unit, slot.texture.clone()); },
+                    false => { // the error texture is bound instead, not cached so the real one is bound once it's registered
+                        self.texture_units.remove(&slot.unit);
+                        texture_error.get_or_insert_with(|| RenderError::MaterialError { material_name: material_name.to_string(), error: format!("texture {} for sampler {} doesn't exist", slot.texture, sampler_name) });
+                    },
+                }
+            }
+            let sampler = UniformValue::Sampler(SamplerBinding { unit: slot.unit, sampler_type: UniformType::Sampler2D }); // the TextureManager only holds 2D textures
+            Self::set_uniform_if_changed(shader, set_uniforms, sampler_name, sampler);
+        }
+
+        for (name, value) in uniforms {
+            Self::set_uniform_if_changed(shader, set_uniforms, name, value.clone());
+        }
+
+        match texture_error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn set_uniform_if_changed(shader: &mut Box<dyn ShaderProgram>, set_uniforms: &mut HashMap<String, UniformValue>, name: &str, value: UniformValue) {
+        if set_uniforms.get(name) != Some(&value) {
+            shader.set_uniform(name.to_string(), &value);
+            set_uniforms.insert(name.to_string(), value);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod material_tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+    use crate::shader::{parse_materials, Material, MaterialInstance, MaterialState, NullableShaderProgram, SamplerBinding, ShaderCall, ShaderHistory, ShaderManager, UniformType, UniformValue};
+    use crate::types::vec4;
+
+    fn register(manager: &mut ShaderManager, name: &str) -> ShaderHistory {
+        let history = ShaderHistory::new();
+        let shader = NullableShaderProgram::new(Rc::new(RefCell::new(HashMap::new())), Rc::new(RefCell::new(false))).with_history(history.clone());
+        manager.register_shader(name.to_string(), Box::new(shader)).unwrap();
+        history
+    }
+
+    #[test]
+    fn apply_skips_redundant_state_changes_test() {
+        let mut manager = ShaderManager::new();
+        let history = register(&mut manager, "LIT");
+        let material = Material::new("BRICK", "LIT").with_uniform("roughness", &0.5).with_texture("albedo", "brick", 2);
+        let mut state = MaterialState::new();
+        let mut bindings = vec![];
+
+        material.apply_with(&mut state, &mut manager, &mut |texture, unit| { bindings.push((texture.to_string(), unit)); true }).unwrap();
+        material.apply_with(&mut state, &mut manager, &mut |texture, unit| { bindings.push((texture.to_string(), unit)); true }).unwrap();
+
+        assert_eq!(history.get_bind_count(), 1);
+        assert_eq!(history.get_uniform_writes("roughness").len(), 1);
+        history.assert_uniform("albedo", &SamplerBinding { unit: 2, sampler_type: UniformType::Sampler2D });
+        assert_eq!(bindings, vec![("brick".to_string(), 2)]);
+
+        state.invalidate_shader("LIT");
+        material.apply_with(&mut state, &mut manager, &mut |_, _| true).unwrap();
+        assert_eq!(history.get_bind_count(), 2);
+        assert_eq!(history.get_uniform_writes("roughness").len(), 2);
+    }
+
+    #[test]
+    fn instance_overrides_test() {
+        let mut manager = ShaderManager::new();
+        let history = register(&mut manager, "LIT");
+        let material = Rc::new(Material::new("BRICK", "LIT").with_uniform("tint", &vec4(1.0, 1.0, 1.0, 1.0)).with_uniform("roughness", &0.5).with_texture("albedo", "brick", 0));
+        let mut red = MaterialInstance::new(material.clone());
+        red.set_uniform("tint", &vec4(1.0, 0.0, 0.0, 1.0));
+        red.set_texture("albedo", "mossy_brick");
+        red.set_texture("detail", "noise");
+        let mut state = MaterialState::new();
+        let mut bindings = vec![];
+
+        red.apply_with(&mut state, &mut manager, &mut |texture, unit| { bindings.push((texture.to_string(), unit)); true }).unwrap();
+        material.apply_with(&mut state, &mut manager, &mut |texture, unit| { bindings.push((texture.to_string(), unit)); true }).unwrap();
+
+        assert_eq!(history.get_uniform_writes("tint"), vec![UniformValue::Vec4(vec4(1.0, 0.0, 0.0, 1.0)), UniformValue::Vec4(vec4(1.0, 1.0, 1.0, 1.0))]);
+        assert_eq!(history.get_uniform_writes("roughness").len(), 1); // same value in both
+        assert_eq!(bindings, vec![("mossy_brick".to_string(), 0), ("noise".to_string(), 1), ("brick".to_string(), 0)]);
+        assert_eq!(red.get_uniform("roughness"), Some(&UniformValue::Float(0.5)));
+    }
+
+    #[test]
+    fn switching_materials_rebinds_shader_test() {
+        let mut manager = ShaderManager::new();
+        let lit = register(&mut manager, "LIT");
+        let unlit = register(&mut manager, "UNLIT");
+        let mut state = MaterialState::new();
+
+        Material::new("BRICK", "LIT").apply_with(&mut state, &mut manager, &mut |_, _| true).unwrap();
+        Material::new("SKY", "UNLIT").apply_with(&mut state, &mut manager, &mut |_, _| true).unwrap();
+        Material::new("BRICK", "LIT").apply_with(&mut state, &mut manager, &mut |_, _| true).unwrap();
+
+        assert_eq!(lit.get_calls(), vec![ShaderCall::Bind, ShaderCall::Bind]);
+        assert_eq!(unlit.get_bind_count(), 1);
+        assert_eq!(state.get_bound_shader(), Some("LIT"));
+        assert!(Material::new("BROKEN", "MISSING").apply_with(&mut state, &mut manager, &mut |_, _| true).is_err());
+    }
+
+    #[test]
+    fn parse_materials_test() {
+        let materials = parse_materials(r#"
+            [BRICK_WALL]
+            shader = "LIT"
+            uniforms = { tint = [1.0, 0.9, 0.9, 1.0], roughness = 0.8 }
+            textures = { albedo = "brick", normal_map = { texture = "brick_normal", unit = 0 } }
+        "#).unwrap();
+
+        let brick = &materials["BRICK_WALL"];
+        assert_eq!(brick.get_name(), "BRICK_WALL");
+        assert_eq!(brick.get_shader_name(), "LIT");
+        assert_eq!(brick.get_uniform("tint"), Some(&UniformValue::Vec4(vec4(1.0, 0.9, 0.9, 1.0))));
+        assert_eq!(brick.get_texture("albedo").unwrap().unit, 1);
+        assert_eq!(brick.get_texture("normal_map").unwrap().unit, 0);
+
+        assert!(parse_materials("[BAD]\nshader = \"LIT\"\nuniforms = { tint = \"red\" }").is_err());
+        assert!(parse_materials("[BAD]\ntextures = { albedo = \"brick\" }").is_err());
+    }
+
+    #[test]
+    fn missing_texture_is_reported_and_not_cached_test() {
+        let mut manager = ShaderManager::new();
+        let history = register(&mut manager, "LIT");
+        let material = Material::new("BRICK", "LIT").with_uniform("roughness", &0.5).with_texture("albedo", "brick", 0);
+        let mut state = MaterialState::new();
+        let mut registered = false;
+        let mut bindings = 0;
+
+        let error = material.apply_with(&mut state, &mut manager, &mut |_, _| { bindings += 1; registered }).unwrap_err();
+        registered = true;
+        material.apply_with(&mut state, &mut manager, &mut |_, _| { bindings += 1; registered }).unwrap();
+        material.apply_with(&mut state, &mut manager, &mut |_, _| { bindings += 1; registered }).unwrap();
+
+        assert!(error.to_string().contains("BRICK") && error.to_string().contains("albedo") && error.to_string().contains("brick"));
+        assert_eq!(bindings, 2);
+        history.assert_uniform("roughness", &0.5); // the rest of the material still applied
+    }
+}
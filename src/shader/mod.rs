@@ -3,11 +3,36 @@ mod gl_shader_program;
 mod nullable_shader_program;
 mod shader_manager;
 mod shader;
+mod shader_preprocessor;
+mod shader_manifest;
+mod shader_reflection;
+mod shader_stages;
+mod builtin_shaders;
+mod program_binary_cache;
+mod shader_diagnostics;
+mod compute_program;
+mod gl_compute_program;
+mod nullable_compute_program;
 mod set_uniform;
+mod uniform_value;
+mod shader_history;
+mod material;
 
 pub use shader_program::ShaderProgram;
 pub use gl_shader_program::GLShaderProgram;
 pub use nullable_shader_program::NullableShaderProgram;
+pub use compute_program::{memory_barrier, work_groups_for, ComputeProgram, MemoryBarrier};
+pub use gl_compute_program::GLComputeProgram;
+pub use nullable_compute_program::NullableComputeProgram;
 pub use shader_manager::ShaderManager;
+pub use shader_reflection::{AttributeInfo, ShaderReflection, UniformBlockInfo, UniformInfo, UniformType};
+pub use builtin_shaders::BuiltinShader;
+pub use shader_diagnostics::{parse_info_log, render_diagnostics, DiagnosticSeverity, ShaderDiagnostic};
+pub use program_binary_cache::{ProgramBinaryCache, ProgramBinaryCacheStats};
+pub use shader_stages::{ShaderStage, ShaderStages, StageSource};
+pub use shader_preprocessor::{inject_defines, PreprocessedSource, ShaderPreprocessor};
 use shader::Shader;
-use set_uniform::SetUniform;
+pub use set_uniform::{SamplerBinding, SetUniform, UniformArrayElement};
+pub use uniform_value::UniformValue;
+pub use shader_history::{ShaderCall, ShaderHistory};
+pub use material::{parse_materials, Material, MaterialInstance, MaterialState, TextureSlot};
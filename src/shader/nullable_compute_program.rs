@@ -0,0 +1,86 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::shader::compute_program::{ComputeProgram, MemoryBarrier};
+use crate::shader::set_uniform::SetUniform;
+
+pub struct NullableComputeProgram {
+    dispatches: Rc<RefCell<Vec<[u32; 3]>>>, // work group counts, in dispatch order
+    barriers: Rc<RefCell<Vec<Vec<MemoryBarrier>>>>,
+    uniform_values: Rc<RefCell<HashMap<String, String>>>,
+    local_size: [u32; 3],
+}
+
+impl ComputeProgram for NullableComputeProgram {
+    fn bind(&self) {}
+
+    fn set_uniform(&mut self, name: String, value: &dyn SetUniform) {
+        self.uniform_values.borrow_mut().insert(name, value.to_string());
+    }
+
+    fn dispatch(&mut self, groups_x: u32, groups_y: u32, groups_z: u32) {
+        self.dispatches.borrow_mut().push([groups_x, groups_y, groups_z]);
+    }
+
+    fn memory_barrier(&mut self, barriers: &[MemoryBarrier]) {
+        self.barriers.borrow_mut().push(barriers.to_vec());
+    }
+
+    fn get_local_size(&self) -> [u32; 3] {
+        self.local_size
+    }
+}
+
+impl NullableComputeProgram {
+    pub fn new(dispatches: Rc<RefCell<Vec<[u32; 3]>>>, barriers: Rc<RefCell<Vec<Vec<MemoryBarrier>>>>, uniform_values: Rc<RefCell<HashMap<String, String>>>, local_size: [u32; 3]) -> Self {
+        Self {
+            dispatches,
+            barriers,
+            uniform_values,
+            local_size,
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod nullable_compute_program_tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+    use crate::shader::{work_groups_for, ComputeProgram, MemoryBarrier, NullableComputeProgram};
+
+    fn compute_program(dispatches: Rc<RefCell<Vec<[u32; 3]>>>, barriers: Rc<RefCell<Vec<Vec<MemoryBarrier>>>>) -> NullableComputeProgram {
+        NullableComputeProgram::new(dispatches, barriers, Rc::new(RefCell::new(HashMap::new())), [64, 1, 1])
+    }
+
+    #[test]
+    fn records_dispatches_and_barriers_test() {
+        let dispatches = Rc::new(RefCell::new(vec![]));
+        let barriers = Rc::new(RefCell::new(vec![]));
+        let mut program = compute_program(dispatches.clone(), barriers.clone());
+
+        program.dispatch(4, 2, 1);
+        program.memory_barrier(&[MemoryBarrier::ShaderStorage, MemoryBarrier::VertexAttribArray]);
+
+        assert_eq!(*dispatches.borrow(), vec![[4, 2, 1]]);
+        assert_eq!(*barriers.borrow(), vec![vec![MemoryBarrier::ShaderStorage, MemoryBarrier::VertexAttribArray]]);
+    }
+
+    #[test]
+    fn dispatch_invocations_rounds_up_test() {
+        let dispatches = Rc::new(RefCell::new(vec![]));
+        let mut program = compute_program(dispatches.clone(), Rc::new(RefCell::new(vec![])));
+
+        program.dispatch_invocations(1000, 1, 1);
+
+        assert_eq!(*dispatches.borrow(), vec![[16, 1, 1]]);
+    }
+
+    #[test]
+    fn work_groups_for_test() {
+        assert_eq!(work_groups_for([256, 100, 1], [16, 16, 1]), [16, 7, 1]);
+        assert_eq!(work_groups_for([0, 1, 1], [8, 8, 1]), [0, 1, 1]);
+    }
+}
@@ -1,23 +1,65 @@
 use std::cell::RefCell;
-use std::collections::{HashMap};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use crate::RenderError;
 use crate::shader::set_uniform::SetUniform;
 use crate::shader::shader_program::ShaderProgram;
+use crate::shader::{ShaderCall, ShaderHistory, ShaderReflection};
 
 pub struct NullableShaderProgram {
     uniform_values: Rc<RefCell<HashMap<String, String>>>,
     bound: Rc<RefCell<bool>>,
+    reflection: Option<ShaderReflection>, // when given, uniforms are checked against it like a real program
+    uniform_errors: Vec<RenderError>,
+    reported_uniforms: HashSet<String>, // like GLShaderProgram, each bad name is only reported once
+    block_bindings: Rc<RefCell<HashMap<String, u32>>>,
+    history: ShaderHistory, // typed record of every call, uniform_values only keeps the latest value of each as a string
 }
 
 impl ShaderProgram for NullableShaderProgram {
     fn bind(&self) {
         self.bound.replace(true);
+        self.history.record(ShaderCall::Bind);
     }
 
-    //Converts all uniform values to Strings.
-    //This is because rust dyn references suck
     fn set_uniform(&mut self, name: String, value: &dyn SetUniform) {
-        self.uniform_values.borrow_mut().insert(name, value.to_string());
+        if let Err(e) = self.try_set_uniform(name.clone(), value) {
+            if self.reported_uniforms.insert(name) {
+                self.uniform_errors.push(e);
+            }
+        }
+    }
+
+    fn try_set_uniform(&mut self, name: String, value: &dyn SetUniform) -> Result<(), RenderError> {
+        if let Some(reflection) = &self.reflection {
+            reflection.check_uniform_value(&name, value)
+                .map_err(|error| RenderError::UniformError { shader_name: "NULLABLE".to_string(), error })?;
+        }
+
+        let value = value.to_uniform_value();
+        self.uniform_values.borrow_mut().insert(name.clone(), value.to_string());
+        self.history.record(ShaderCall::SetUniform { name, value });
+        Ok(())
+    }
+
+    fn get_reflection(&self) -> Option<&ShaderReflection> {
+        self.reflection.as_ref()
+    }
+
+    fn take_uniform_errors(&mut self) -> Vec<RenderError> {
+        std::mem::take(&mut self.uniform_errors)
+    }
+
+    fn bind_uniform_block(&mut self, block_name: &str, binding: u32) -> Result<(), RenderError> {
+        if let Some(reflection) = &self.reflection {
+            if reflection.get_uniform_block(block_name).is_none() {
+                return Err(RenderError::UniformError { shader_name: "NULLABLE".to_string(), error: format!("{} isn't an active uniform block", block_name) });
+            }
+        }
+
+        self.block_bindings.borrow_mut().insert(block_name.to_string(), binding);
+        self.history.record(ShaderCall::BindUniformBlock { block_name: block_name.to_string(), binding });
+        Ok(())
     }
 }
 
@@ -26,8 +68,28 @@ impl NullableShaderProgram {
         Self {
             uniform_values,
             bound,
+            reflection: None,
+            uniform_errors: vec![],
+            reported_uniforms: HashSet::new(),
+            block_bindings: Rc::new(RefCell::new(HashMap::new())),
+            history: ShaderHistory::new(),
         }
     }
+
+    pub fn with_history(mut self, history: ShaderHistory) -> Self { // shares the history, see ShaderHistory's assertion helpers
+        self.history = history;
+        self
+    }
+
+    pub fn with_block_bindings(mut self, block_bindings: Rc<RefCell<HashMap<String, u32>>>) -> Self { // records bind_uniform_block calls
+        self.block_bindings = block_bindings;
+        self
+    }
+
+    pub fn with_reflection(mut self, reflection: ShaderReflection) -> Self {
+        self.reflection = Some(reflection);
+        self
+    }
 }
 
 
@@ -37,7 +99,10 @@ mod nullable_shader_program_tests {
     use std::cell::RefCell;
     use std::collections::HashMap;
     use std::rc::Rc;
-    use crate::shader::{NullableShaderProgram, ShaderProgram};
+    use mockall::predicate::eq;
+    use crate::shader::{NullableShaderProgram, ShaderProgram, ShaderReflection, UniformInfo, UniformType};
+    use crate::texture::MockTexture2D;
+    use crate::types::{ivec3, Mat3};
 
     #[test]
     fn bind_test() {
@@ -60,4 +125,78 @@ mod nullable_shader_program_tests {
 
         assert_eq!(*uniform_values.borrow().get("test").unwrap(), value.to_string());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn set_uniform_checks_reflection_test() {
+        let reflection = ShaderReflection {
+            uniforms: vec![UniformInfo { name: "brightness".to_string(), uniform_type: UniformType::Float, array_size: 1, location: 0, block_index: None }],
+            ..ShaderReflection::default()
+        };
+        let uniform_values = Rc::new(RefCell::new(HashMap::new()));
+        let mut shader_program = NullableShaderProgram::new(uniform_values.clone(), Rc::new(RefCell::new(false))).with_reflection(reflection);
+
+        shader_program.set_uniform("brightness".to_string(), &1);
+        shader_program.set_uniform("brightnes".to_string(), &1.0);
+        shader_program.set_uniform("brightness".to_string(), &0.5);
+
+        assert_eq!(shader_program.take_uniform_errors().len(), 2);
+        assert!(shader_program.try_set_uniform("missing".to_string(), &1.0).is_err());
+        assert_eq!(*uniform_values.borrow(), HashMap::from([("brightness".to_string(), 0.5.to_string())]));
+    }
+
+    #[test]
+    fn bad_uniform_is_only_reported_once_test() {
+        let reflection = ShaderReflection {
+            uniforms: vec![UniformInfo { name: "brightness".to_string(), uniform_type: UniformType::Float, array_size: 1, location: 0, block_index: None }],
+            ..ShaderReflection::default()
+        };
+        let mut shader_program = NullableShaderProgram::new(Rc::new(RefCell::new(HashMap::new())), Rc::new(RefCell::new(false))).with_reflection(reflection);
+
+        shader_program.set_uniform("brightnes".to_string(), &1.0);
+        shader_program.set_uniform("brightnes".to_string(), &1.0);
+
+        assert_eq!(shader_program.take_uniform_errors().len(), 1);
+    }
+
+    #[test]
+    fn records_arrays_and_other_value_types_test() {
+        let uniform_values = Rc::new(RefCell::new(HashMap::new()));
+        let mut shader_program = NullableShaderProgram::new(uniform_values.clone(), Rc::new(RefCell::new(false)));
+        let weights = vec![0.5_f32, 0.25];
+
+        shader_program.set_uniform("offsets".to_string(), &[ivec3(1, 2, 3), ivec3(4, 5, 6)]);
+        shader_program.set_uniform("weights".to_string(), &weights.as_slice());
+        shader_program.set_uniform("frame".to_string(), &7_u32);
+        shader_program.set_uniform("use_fog".to_string(), &true);
+        shader_program.set_uniform("normal_matrix".to_string(), &Mat3::identity());
+
+        let uniform_values = uniform_values.borrow();
+        assert_eq!(uniform_values["offsets"], format!("[{}, {}]", ivec3(1, 2, 3), ivec3(4, 5, 6)));
+        assert_eq!(uniform_values["weights"], "[0.5, 0.25]");
+        assert_eq!(uniform_values["frame"], "7");
+        assert_eq!(uniform_values["use_fog"], "true");
+        assert_eq!(uniform_values["normal_matrix"], Mat3::identity().to_string());
+    }
+
+    #[test]
+    fn set_sampler_binds_texture_to_unit_test() {
+        let reflection = ShaderReflection {
+            uniforms: vec![
+                UniformInfo { name: "albedo".to_string(), uniform_type: UniformType::Sampler2D, array_size: 1, location: 0, block_index: None },
+                UniformInfo { name: "volume".to_string(), uniform_type: UniformType::Sampler3D, array_size: 1, location: 1, block_index: None },
+            ],
+            ..ShaderReflection::default()
+        };
+        let uniform_values = Rc::new(RefCell::new(HashMap::new()));
+        let mut shader_program = NullableShaderProgram::new(uniform_values.clone(), Rc::new(RefCell::new(false))).with_reflection(reflection);
+        let mut texture = MockTexture2D::default();
+        texture.expect_bind_to_unit().with(eq(3)).times(2).return_const(());
+
+        shader_program.set_sampler("albedo".to_string(), &texture, 3);
+        shader_program.set_sampler("volume".to_string(), &texture, 3); // a 2D texture can't go in a sampler3D
+
+        assert_eq!(uniform_values.borrow()["albedo"], "Sampler2D(3)");
+        assert!(!uniform_values.borrow().contains_key("volume"));
+        assert_eq!(shader_program.take_uniform_errors().len(), 1);
+    }
+}
@@ -0,0 +1,246 @@
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use gl::types::{GLenum, GLint};
+use crate::shader::ShaderStage;
+
+const CACHE_MAGIC: &[u8; 4] = b"DGPB";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProgramBinaryCacheStats {
+    pub hits: u32,
+    pub misses: u32, // no readable entry, so the program was compiled
+    pub rejected: u32, // an entry the driver refused to link, also compiled
+    pub stored: u32,
+    pub store_failures: u32,
+}
+
+// Linked program binaries on disk, keyed by a hash of the stages' final sources, the defines and the driver.
+// Any mismatch or failure just falls back to compiling, so the directory can be deleted at any time.
+// Clones share their statistics, e.g. between every loader the ShaderManager holds.
+#[derive(Debug, Clone)]
+pub struct ProgramBinaryCache {
+    directory: PathBuf,
+    driver_id: String, // vendor, renderer and version; binaries are only valid for the driver that made them
+    enabled: bool,
+    stats: Rc<Cell<ProgramBinaryCacheStats>>,
+}
+
+impl ProgramBinaryCache {
+    pub fn new(directory: &Path) -> ProgramBinaryCache { // needs a current GL context, disabled if the driver has no binary formats
+        let driver_id = [gl::VENDOR, gl::RENDERER, gl::VERSION].iter()
+            .map(|name| unsafe {
+                let string = gl::GetString(*name);
+                if string.is_null() { String::new() } else { CStr::from_ptr(string.cast()).to_string_lossy().to_string() }
+            })
+            .collect::<Vec<String>>()
+            .join(" | ");
+
+        let mut format_count: GLint = 0;
+        if gl::GetProgramBinary::is_loaded() && gl::ProgramBinary::is_loaded() {
+            unsafe { gl::GetIntegerv(gl::NUM_PROGRAM_BINARY_FORMATS, &mut format_count); }
+        }
+
+        let mut cache = Self::with_driver_id(directory, &driver_id);
+        cache.enabled = format_count > 0;
+        cache
+    }
+
+    pub fn with_driver_id(directory: &Path, driver_id: &str) -> ProgramBinaryCache {
+        ProgramBinaryCache { directory: directory.to_path_buf(), driver_id: driver_id.to_string(), enabled: true, stats: Rc::new(Cell::new(ProgramBinaryCacheStats::default())) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn get_directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn get_stats(&self) -> ProgramBinaryCacheStats {
+        self.stats.get()
+    }
+
+    pub fn reset_stats(&self) {
+        self.stats.set(ProgramBinaryCacheStats::default());
+    }
+
+    fn update_stats(&self, update: impl FnOnce(&mut ProgramBinaryCacheStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+
+    pub fn key(&self, stage_sources: &[(ShaderStage, &str)], defines: &[String]) -> u64 {
+        let mut hasher = Fnv1a::new();
+        hasher.write_str(&self.driver_id);
+
+        for (stage, source) in stage_sources {
+            hasher.write_str(stage.get_name());
+            hasher.write_str(source);
+        }
+        for define in defines {
+            hasher.write_str(define);
+        }
+
+        hasher.finish()
+    }
+
+    fn entry_path(&self, key: u64) -> PathBuf {
+        self.directory.join(format!("{:016x}.bin", key))
+    }
+
+    pub fn load(&self, key: u64) -> Option<(GLenum, Vec<u8>)> { // counts a miss when there's no usable entry
+        if !self.enabled {
+            return None;
+        }
+
+        let entry = std::fs::read(self.entry_path(key)).ok().and_then(|bytes| decode_entry(&bytes));
+        if entry.is_none() {
+            self.update_stats(|stats| stats.misses += 1);
+        }
+
+        entry
+    }
+
+    pub fn record_hit(&self) {
+        self.update_stats(|stats| stats.hits += 1);
+    }
+
+    pub fn reject(&self, key: u64) { // the driver wouldn't link the binary, e.g. after an update that kept the version string
+        let _ = std::fs::remove_file(self.entry_path(key));
+        self.update_stats(|stats| stats.rejected += 1);
+    }
+
+    pub fn store(&self, key: u64, format: GLenum, binary: &[u8]) {
+        if !self.enabled {
+            return;
+        }
+
+        let path = self.entry_path(key);
+        let temp_path = path.with_extension("tmp"); // renamed into place so a crash never leaves half an entry
+
+        let result = std::fs::create_dir_all(&self.directory)
+            .and_then(|_| std::fs::write(&temp_path, encode_entry(format, binary)))
+            .and_then(|_| std::fs::rename(&temp_path, &path));
+
+        match result {
+            Ok(_) => self.update_stats(|stats| stats.stored += 1),
+            Err(_) => self.update_stats(|stats| stats.store_failures += 1),
+        }
+    }
+}
+
+struct Fnv1a { // stable between runs and compiler versions, unlike DefaultHasher
+    hash: u64,
+}
+
+impl Fnv1a {
+    fn new() -> Fnv1a {
+        Fnv1a { hash: 0xcbf29ce484222325 }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_str(&mut self, string: &str) { // length prefixed so ("ab", "c") and ("a", "bc") differ
+        self.write(&(string.len() as u64).to_le_bytes());
+        self.write(string.as_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+fn encode_entry(format: GLenum, binary: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(binary.len() + 12);
+    bytes.extend_from_slice(CACHE_MAGIC);
+    bytes.extend_from_slice(&format.to_le_bytes());
+    bytes.extend_from_slice(&(binary.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(binary);
+    bytes
+}
+
+fn decode_entry(bytes: &[u8]) -> Option<(GLenum, Vec<u8>)> {
+    if bytes.len() < 12 || &bytes[0..4] != CACHE_MAGIC {
+        return None;
+    }
+
+    let format = GLenum::from_le_bytes(bytes[4..8].try_into().ok()?);
+    let length = u32::from_le_bytes(bytes[8..12].try_into().ok()?) as usize;
+    let binary = &bytes[12..];
+
+    match binary.len() == length && length > 0 {
+        true => Some((format, binary.to_vec())),
+        false => None, // truncated write
+    }
+}
+
+
+#[cfg(test)]
+mod program_binary_cache_tests {
+    use std::path::PathBuf;
+    use crate::shader::{ProgramBinaryCache, ShaderStage};
+
+    fn temp_directory(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dec_gl_binary_cache_{}_{}", name, std::process::id()))
+    }
+
+    fn sources(fragment: &str) -> Vec<(ShaderStage, &str)> {
+        vec![(ShaderStage::Vertex, "void main() {}"), (ShaderStage::Fragment, fragment)]
+    }
+
+    #[test]
+    fn key_covers_sources_defines_and_driver_test() {
+        let cache = ProgramBinaryCache::with_driver_id(&temp_directory("key"), "Vendor | GPU | 4.6");
+        let key = cache.key(&sources("a"), &[]);
+
+        assert_eq!(key, cache.key(&sources("a"), &[]));
+        assert_ne!(key, cache.key(&sources("b"), &[]));
+        assert_ne!(key, cache.key(&sources("a"), &["SHADOWS".to_string()]));
+        assert_ne!(key, ProgramBinaryCache::with_driver_id(&temp_directory("key"), "Vendor | GPU | 4.5").key(&sources("a"), &[]));
+    }
+
+    #[test]
+    fn store_and_load_test() {
+        let directory = temp_directory("store");
+        let cache = ProgramBinaryCache::with_driver_id(&directory, "driver");
+        let key = cache.key(&sources("a"), &[]);
+
+        assert_eq!(cache.load(key), None);
+        cache.store(key, 0x8e21, &[1, 2, 3, 4]);
+        assert_eq!(cache.clone().load(key), Some((0x8e21, vec![1, 2, 3, 4])));
+
+        cache.reject(key);
+        assert_eq!(cache.load(key), None);
+
+        let stats = cache.get_stats();
+        assert_eq!((stats.misses, stats.stored, stats.rejected), (2, 1, 1));
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn corrupt_entries_are_misses_test() {
+        let directory = temp_directory("corrupt");
+        let cache = ProgramBinaryCache::with_driver_id(&directory, "driver");
+        let key = cache.key(&sources("a"), &[]);
+
+        cache.store(key, 1, &[1, 2, 3, 4]);
+        let path = directory.join(format!("{:016x}.bin", key));
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap(); // truncated
+
+        assert_eq!(cache.load(key), None);
+        assert_eq!(cache.get_stats().misses, 1);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,49 +1,186 @@
-use std::fmt::Display;
+use std::fmt::{Display, Formatter};
 use gl::types::GLint;
-use crate::types::{IVec2, Mat4, Vec2, Vec3, Vec4};
+use crate::shader::{UniformType, UniformValue};
+use crate::types::{IVec2, IVec3, IVec4, Mat3, Mat4, UVec2, Vec2, Vec3, Vec4};
 
-pub trait SetUniform: Display { // allows for any type to be settable if you implement SetUniform for it
+pub trait SetUniform { // allows for any type to be settable if you implement SetUniform for it
+    /// # Safety
+    /// Needs a current GL context with the program that owns `location` bound.
     unsafe fn set_uniform(&self, location: GLint);
-}
 
-impl SetUniform for Mat4 {
-    unsafe fn set_uniform(&self, location: GLint) {
-        gl::UniformMatrix4fv(location, 1, gl::FALSE, self.as_array().as_ptr() as *const f32) ;
+    fn to_uniform_value(&self) -> UniformValue; // what NullableShaderProgram records, UniformValue::Other for types without a typed form
+
+    fn uniform_type(&self) -> Option<UniformType> { // used to check against the shader's reflection, None skips the check
+        None
     }
-}
 
-impl SetUniform for Vec2 {
-    unsafe fn set_uniform(&self, location: GLint) {
-        gl::Uniform2fv(location, 1, self.as_array().as_ptr()) ;
+    fn array_len(&self) -> Option<usize> { // Some for arrays, so writing past the end of the GLSL array is caught
+        None
     }
 }
 
-impl SetUniform for Vec3 {
-    unsafe fn set_uniform(&self, location: GLint) {
-        gl::Uniform3fv(location, 1, self.as_array().as_ptr()) ;
+impl Display for dyn SetUniform + '_ {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_uniform_value())
     }
 }
 
-impl SetUniform for Vec4 {
+// Types that can also be set as a whole array with one glUniform*v call, e.g. set_uniform("lights", &[vec3(...), vec3(...)]).
+pub trait UniformArrayElement: SetUniform + Sized {
+    const UNIFORM_TYPE: UniformType;
+
+    /// # Safety
+    /// As for `SetUniform::set_uniform`, and the uniform at `location` must have room for every value.
+    unsafe fn set_uniform_array(values: &[Self], location: GLint);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SamplerBinding { // the texture unit a sampler reads from, typed so a sampler2D can't be pointed at a 3D texture
+    pub unit: u32,
+    pub sampler_type: UniformType,
+}
+
+impl SetUniform for SamplerBinding {
     unsafe fn set_uniform(&self, location: GLint) {
-        gl::Uniform4fv(location, 1, self.as_array().as_ptr()) ;
+        gl::Uniform1i(location, self.unit as GLint);
+    }
+
+    fn to_uniform_value(&self) -> UniformValue {
+        UniformValue::Sampler(*self)
+    }
+
+    fn uniform_type(&self) -> Option<UniformType> {
+        Some(self.sampler_type)
     }
 }
 
-impl SetUniform for IVec2 {
+macro_rules! impl_set_uniform { // single values go through the array call with a count of 1
+    ($value_type:ty, $variant:ident, |$values:ident, $location:ident| $set_array:expr) => {
+        impl SetUniform for $value_type {
+            unsafe fn set_uniform(&self, location: GLint) {
+                Self::set_uniform_array(std::slice::from_ref(self), location);
+            }
+
+            fn to_uniform_value(&self) -> UniformValue {
+                UniformValue::$variant(*self)
+            }
+
+            fn uniform_type(&self) -> Option<UniformType> {
+                Some(UniformType::$variant)
+            }
+        }
+
+        impl UniformArrayElement for $value_type {
+            const UNIFORM_TYPE: UniformType = UniformType::$variant;
+
+            unsafe fn set_uniform_array($values: &[Self], $location: GLint) {
+                $set_array
+            }
+        }
+    };
+}
+
+impl_set_uniform!(f32, Float, |values, location| gl::Uniform1fv(location, values.len() as i32, values.as_ptr()));
+impl_set_uniform!(i32, Int, |values, location| gl::Uniform1iv(location, values.len() as i32, values.as_ptr()));
+impl_set_uniform!(u32, UInt, |values, location| gl::Uniform1uiv(location, values.len() as i32, values.as_ptr()));
+impl_set_uniform!(bool, Bool, |values, location| { // GL takes bools as ints
+    let ints: Vec<GLint> = values.iter().map(|value| *value as GLint).collect();
+    gl::Uniform1iv(location, values.len() as i32, ints.as_ptr())
+});
+// the vector and matrix types aren't repr(C), so they're flattened through as_array rather than cast
+impl_set_uniform!(Vec2, Vec2, |values, location| {
+    let floats: Vec<f32> = values.iter().flat_map(|value| value.as_array()).collect();
+    gl::Uniform2fv(location, values.len() as i32, floats.as_ptr())
+});
+impl_set_uniform!(Vec3, Vec3, |values, location| {
+    let floats: Vec<f32> = values.iter().flat_map(|value| value.as_array()).collect();
+    gl::Uniform3fv(location, values.len() as i32, floats.as_ptr())
+});
+impl_set_uniform!(Vec4, Vec4, |values, location| {
+    let floats: Vec<f32> = values.iter().flat_map(|value| value.as_array()).collect();
+    gl::Uniform4fv(location, values.len() as i32, floats.as_ptr())
+});
+impl_set_uniform!(IVec2, IVec2, |values, location| {
+    let ints: Vec<i32> = values.iter().flat_map(|value| value.as_array()).collect();
+    gl::Uniform2iv(location, values.len() as i32, ints.as_ptr())
+});
+impl_set_uniform!(IVec3, IVec3, |values, location| {
+    let ints: Vec<i32> = values.iter().flat_map(|value| value.as_array()).collect();
+    gl::Uniform3iv(location, values.len() as i32, ints.as_ptr())
+});
+impl_set_uniform!(IVec4, IVec4, |values, location| {
+    let ints: Vec<i32> = values.iter().flat_map(|value| value.as_array()).collect();
+    gl::Uniform4iv(location, values.len() as i32, ints.as_ptr())
+});
+impl_set_uniform!(UVec2, UVec2, |values, location| {
+    let uints: Vec<u32> = values.iter().flat_map(|value| value.as_array()).collect();
+    gl::Uniform2uiv(location, values.len() as i32, uints.as_ptr())
+});
+impl_set_uniform!(Mat3, Mat3, |values, location| {
+    let floats: Vec<f32> = values.iter().flat_map(|value| value.as_array()).flat_map(|column| column.as_array()).collect();
+    gl::UniformMatrix3fv(location, values.len() as i32, gl::FALSE, floats.as_ptr())
+});
+impl_set_uniform!(Mat4, Mat4, |values, location| {
+    let floats: Vec<f32> = values.iter().flat_map(|value| value.as_array()).flat_map(|column| column.as_array()).collect();
+    gl::UniformMatrix4fv(location, values.len() as i32, gl::FALSE, floats.as_ptr())
+});
+
+fn array_to_value<T: SetUniform>(values: &[T]) -> UniformValue {
+    UniformValue::Array(values.iter().map(|value| value.to_uniform_value()).collect())
+}
+
+impl<T: UniformArrayElement, const N: usize> SetUniform for [T; N] {
     unsafe fn set_uniform(&self, location: GLint) {
-        gl::Uniform2i(location, self.x, self.y) ;
+        self.as_slice().set_uniform(location);
+    }
+
+    fn to_uniform_value(&self) -> UniformValue {
+        array_to_value(self)
+    }
+
+    fn uniform_type(&self) -> Option<UniformType> {
+        Some(T::UNIFORM_TYPE)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        Some(N)
     }
 }
 
-impl SetUniform for f32 {
+impl<T: UniformArrayElement> SetUniform for Vec<T> {
     unsafe fn set_uniform(&self, location: GLint) {
-        gl::Uniform1f(location, self.clone());
+        self.as_slice().set_uniform(location);
+    }
+
+    fn to_uniform_value(&self) -> UniformValue {
+        array_to_value(self)
+    }
+
+    fn uniform_type(&self) -> Option<UniformType> {
+        Some(T::UNIFORM_TYPE)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        Some(self.len())
     }
 }
 
-impl SetUniform for i32{
+impl<T: UniformArrayElement> SetUniform for &[T] {
     unsafe fn set_uniform(&self, location: GLint) {
-        gl::Uniform1i(location, self.clone());
+        if !self.is_empty() {
+            T::set_uniform_array(self, location);
+        }
+    }
+
+    fn to_uniform_value(&self) -> UniformValue {
+        array_to_value(self)
     }
-}
\ No newline at end of file
+
+    fn uniform_type(&self) -> Option<UniformType> {
+        Some(T::UNIFORM_TYPE)
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
@@ -6,10 +6,6 @@ pub struct Shader {
 
 
 impl Shader {
-
-    pub fn empty_shader () -> Shader {
-        Shader { id: 0 }
-    }
     pub fn load_and_compile_shader(source: &String, shader_type: GLenum)  -> Result<Shader, String>{
         let mut frag_shader = Shader { id: 0 };
 
@@ -32,17 +28,16 @@ impl Shader {
             let mut success = 0;
             gl::GetShaderiv(frag_shader.id, gl::COMPILE_STATUS, &mut success);
             if success == 0 {
-                let mut v: Vec<u8> = Vec::with_capacity(1024);
                 let mut log_len = 0_i32;
+                gl::GetShaderiv(frag_shader.id, gl::INFO_LOG_LENGTH, &mut log_len); // the whole log, a long one is where the useful errors are
+
+                let mut v: Vec<u8> = vec![0; log_len.max(1) as usize];
+                let mut written = 0_i32;
+                gl::GetShaderInfoLog(frag_shader.id, v.len() as i32, &mut written, v.as_mut_ptr().cast());
+                v.truncate(written.max(0) as usize);
 
-                gl::GetShaderInfoLog(
-                    frag_shader.id,
-                    1024,
-                    &mut log_len,
-                    v.as_mut_ptr().cast(),
-                );
-                v.set_len(log_len.try_into().unwrap());
-                return Err(format!("Compilation Error: {}", String::from_utf8_lossy(&v).to_string()));
+                frag_shader.delete();
+                return Err(String::from_utf8_lossy(&v).to_string()); // the raw info log, parsed by the caller
             }
         }
 
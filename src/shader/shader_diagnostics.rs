@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticSeverity::Error => write!(f, "error"),
+            DiagnosticSeverity::Warning => write!(f, "warning"),
+            DiagnosticSeverity::Info => write!(f, "info"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderDiagnostic { // one entry of a driver's info log
+    pub file: Option<usize>, // the source string number, i.e. the file index from the #line directives
+    pub line: Option<usize>,
+    pub column: Option<usize>, // only Mesa reports these
+    pub severity: DiagnosticSeverity,
+    pub code: Option<String>, // e.g. NVIDIA's C1008
+    pub message: String,
+}
+
+impl ShaderDiagnostic {
+    fn without_location(severity: DiagnosticSeverity, message: &str) -> ShaderDiagnostic {
+        ShaderDiagnostic { file: None, line: None, column: None, severity, code: None, message: message.to_string() }
+    }
+}
+
+// Drivers don't agree on a format, these cover the common ones:
+//
+// NVIDIA:      0(12) : error C1008: undefined variable "colour"
+// Mesa:        0:12(5): error: `colour' undeclared
+// AMD, Apple:  ERROR: 0:12: 'colour' : undeclared identifier
+//
+// Lines that match none of them continue the previous diagnostic's message, or stand alone if there isn't one.
+pub fn parse_info_log(log: &str) -> Vec<ShaderDiagnostic> {
+    let mut diagnostics: Vec<ShaderDiagnostic> = vec![];
+
+    for line in log.lines().map(|line| line.trim_end_matches('\0').trim_end()) {
+        if line.trim().is_empty() || is_summary_line(line) {
+            continue;
+        }
+
+        match parse_nvidia_line(line).or_else(|| parse_mesa_line(line)).or_else(|| parse_amd_line(line)) {
+            Some(diagnostic) => diagnostics.push(diagnostic),
+            None => match diagnostics.last_mut() {
+                Some(previous) => {
+                    previous.message.push('\n');
+                    previous.message.push_str(line.trim());
+                },
+                None => diagnostics.push(ShaderDiagnostic::without_location(severity_from_word(line).unwrap_or(DiagnosticSeverity::Error), line.trim())),
+            },
+        }
+    }
+
+    diagnostics
+}
+
+fn is_summary_line(line: &str) -> bool { // e.g. AMD's "ERROR: 2 compilation errors.  No code generated."
+    line.contains("compilation errors") && line.contains("No code generated")
+}
+
+fn severity_from_word(word: &str) -> Option<DiagnosticSeverity> { // also matches Mesa's "preprocessor error"
+    let word = word.to_lowercase();
+    if word.contains("error") { return Some(DiagnosticSeverity::Error) }
+    if word.contains("warning") { return Some(DiagnosticSeverity::Warning) }
+    if word.contains("info") || word.contains("note") { return Some(DiagnosticSeverity::Info) }
+    None
+}
+
+fn take_number(text: &str) -> Option<(usize, &str)> {
+    let end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    if end == 0 {
+        return None;
+    }
+
+    Some((text[..end].parse().ok()?, &text[end..]))
+}
+
+fn parse_nvidia_line(line: &str) -> Option<ShaderDiagnostic> { // FILE(LINE) : SEVERITY CODE: MESSAGE
+    let (file, rest) = take_number(line.trim_start())?;
+    let (line_number, rest) = take_number(rest.strip_prefix('(')?)?;
+    let rest = rest.strip_prefix(')')?.trim_start().strip_prefix(':')?.trim_start();
+
+    let (heading, message) = rest.split_once(':')?;
+    let mut heading_words = heading.split_whitespace();
+    let severity = severity_from_word(heading_words.next()?)?;
+    let code = heading_words.next().map(|code| code.to_string());
+
+    Some(ShaderDiagnostic { file: Some(file), line: Some(line_number), column: None, severity, code, message: message.trim().to_string() })
+}
+
+fn parse_mesa_line(line: &str) -> Option<ShaderDiagnostic> { // FILE:LINE(COLUMN): SEVERITY: MESSAGE
+    let (file, rest) = take_number(line.trim_start())?;
+    let (line_number, rest) = take_number(rest.strip_prefix(':')?)?;
+    let (column, rest) = take_number(rest.strip_prefix('(')?)?;
+    let rest = rest.strip_prefix("):")?;
+
+    let (heading, message) = rest.split_once(':')?;
+    let severity = severity_from_word(heading)?;
+
+    Some(ShaderDiagnostic { file: Some(file), line: Some(line_number), column: Some(column), severity, code: None, message: message.trim().to_string() })
+}
+
+fn parse_amd_line(line: &str) -> Option<ShaderDiagnostic> { // SEVERITY: FILE:LINE: MESSAGE
+    let (heading, rest) = line.trim_start().split_once(':')?;
+    let severity = match heading {
+        "ERROR" => DiagnosticSeverity::Error,
+        "WARNING" => DiagnosticSeverity::Warning,
+        "INFO" => DiagnosticSeverity::Info,
+        _ => return None,
+    };
+
+    let located = take_number(rest.trim_start())
+        .and_then(|(file, rest)| take_number(rest.strip_prefix(':')?).map(|(line_number, rest)| (file, line_number, rest)))
+        .and_then(|(file, line_number, rest)| rest.strip_prefix(':').map(|message| (file, line_number, message)));
+
+    match located {
+        Some((file, line_number, message)) => Some(ShaderDiagnostic { file: Some(file), line: Some(line_number), column: None, severity, code: None, message: message.trim().to_string() }),
+        None => Some(ShaderDiagnostic::without_location(severity, rest.trim())),
+    }
+}
+
+// The line each (file, line) in a diagnostic refers to, found by following the #line directives in the source given to the driver.
+struct SourceLines<'a> {
+    lines: HashMap<(usize, usize), &'a str>,
+}
+
+impl<'a> SourceLines<'a> {
+    fn new(compiled_source: &'a str) -> SourceLines<'a> {
+        let mut lines = HashMap::new();
+        let (mut file, mut line_number) = (0, 1);
+
+        for line in compiled_source.lines() {
+            let directive: Vec<&str> = line.trim().strip_prefix("#line").map(|rest| rest.split_whitespace().collect()).unwrap_or_default();
+            if let Some(Ok(next_line)) = directive.first().map(|number| number.parse::<usize>()) {
+                line_number = next_line; // the directive sets the number of the line after it
+                file = directive.get(1).and_then(|number| number.parse().ok()).unwrap_or(file);
+                continue;
+            }
+
+            lines.insert((file, line_number), line);
+            line_number += 1;
+        }
+
+        SourceLines { lines }
+    }
+
+    fn get(&self, file: usize, line: usize) -> Option<&'a str> {
+        self.lines.get(&(file, line)).copied()
+    }
+}
+
+// A report in the style of rustc's:
+//
+// error[C1008]: undefined variable "colour"
+//   --> shaders/lit.fsh:12
+//    |
+// 11 |     vec3 n = normalize(normal);
+// 12 |     colour = vec4(n, 1.0);
+//    |     ^
+pub fn render_diagnostics(diagnostics: &[ShaderDiagnostic], compiled_source: &str, file_name: &dyn Fn(usize) -> Option<String>) -> String {
+    let source_lines = SourceLines::new(compiled_source);
+    let mut report = String::new();
+
+    for diagnostic in diagnostics {
+        match &diagnostic.code {
+            Some(code) => report.push_str(&format!("{}[{}]: {}\n", diagnostic.severity, code, diagnostic.message)),
+            None => report.push_str(&format!("{}: {}\n", diagnostic.severity, diagnostic.message)),
+        }
+
+        let (file, line) = match (diagnostic.file, diagnostic.line) {
+            (Some(file), Some(line)) => (file, line),
+            _ => continue,
+        };
+
+        let name = file_name(file).unwrap_or_else(|| format!("<source {}>", file));
+        let gutter = " ".repeat((line + 1).to_string().len());
+        match diagnostic.column {
+            Some(column) => report.push_str(&format!("{}--> {}:{}:{}\n", gutter, name, line, column)),
+            None => report.push_str(&format!("{}--> {}:{}\n", gutter, name, line)),
+        }
+
+        let excerpt: Vec<(usize, &str)> = (line.saturating_sub(1).max(1)..=line + 1)
+            .filter_map(|number| source_lines.get(file, number).map(|text| (number, text)))
+            .collect();
+        if excerpt.is_empty() {
+            continue;
+        }
+
+        report.push_str(&format!("{} |\n", gutter));
+        for (number, text) in excerpt {
+            report.push_str(&format!("{:>width$} | {}\n", number, text, width = gutter.len()));
+
+            if number == line {
+                let indent = match diagnostic.column {
+                    Some(column) => column.saturating_sub(1),
+                    None => text.len() - text.trim_start().len(), // point at the start of the code
+                };
+                report.push_str(&format!("{} | {}^\n", gutter, " ".repeat(indent)));
+            }
+        }
+    }
+
+    report
+}
+
+
+#[cfg(test)]
+mod shader_diagnostics_tests {
+    use crate::shader::{parse_info_log, render_diagnostics, DiagnosticSeverity, ShaderDiagnostic};
+
+    fn located(file: usize, line: usize, column: Option<usize>, severity: DiagnosticSeverity, code: Option<&str>, message: &str) -> ShaderDiagnostic {
+        ShaderDiagnostic { file: Some(file), line: Some(line), column, severity, code: code.map(|code| code.to_string()), message: message.to_string() }
+    }
+
+    #[test]
+    fn parses_nvidia_log_test() {
+        let log = "0(12) : error C1008: undefined variable \"colour\"\n1(4) : warning C7050: \"light\" might be used before being initialized\n\0";
+
+        assert_eq!(parse_info_log(log), vec![
+            located(0, 12, None, DiagnosticSeverity::Error, Some("C1008"), "undefined variable \"colour\""),
+            located(1, 4, None, DiagnosticSeverity::Warning, Some("C7050"), "\"light\" might be used before being initialized"),
+        ]);
+    }
+
+    #[test]
+    fn parses_mesa_log_test() {
+        let log = "0:12(5): error: `colour' undeclared\n0:12(5): error: value of type vec4 cannot be assigned to variable of type float\n0:1(10): preprocessor error: Invalid #version\n";
+
+        assert_eq!(parse_info_log(log), vec![
+            located(0, 12, Some(5), DiagnosticSeverity::Error, None, "`colour' undeclared"),
+            located(0, 12, Some(5), DiagnosticSeverity::Error, None, "value of type vec4 cannot be assigned to variable of type float"),
+            located(0, 1, Some(10), DiagnosticSeverity::Error, None, "Invalid #version"),
+        ]);
+    }
+
+    #[test]
+    fn parses_amd_log_test() {
+        let log = "ERROR: 0:12: 'colour' : undeclared identifier \nWARNING: 2:3: 'x' : unused variable\nERROR: 2 compilation errors.  No code generated.\n\n";
+
+        assert_eq!(parse_info_log(log), vec![
+            located(0, 12, None, DiagnosticSeverity::Error, None, "'colour' : undeclared identifier"),
+            located(2, 3, None, DiagnosticSeverity::Warning, None, "'x' : unused variable"),
+        ]);
+    }
+
+    #[test]
+    fn unknown_lines_are_kept_test() {
+        let diagnostics = parse_info_log("Internal compiler error\n  while optimising\n");
+
+        assert_eq!(diagnostics, vec![ShaderDiagnostic { file: None, line: None, column: None, severity: DiagnosticSeverity::Error, code: None, message: "Internal compiler error\nwhile optimising".to_string() }]);
+    }
+
+    #[test]
+    fn report_maps_lines_through_includes_test() {
+        let source = "#version 330 core\n#line 1 1\nvec3 light() {\n    return colour;\n}\n#line 3 0\nvoid main() {\n    gl_FragColor = vec4(light(), 1.0);\n}\n";
+        let diagnostics = parse_info_log("1:2(12): error: `colour' undeclared\n");
+
+        let report = render_diagnostics(&diagnostics, source, &|file| ["lit.fsh", "lighting.glsl"].get(file).map(|name| name.to_string()));
+
+        assert_eq!(report, [
+            "error: `colour' undeclared",
+            " --> lighting.glsl:2:12",
+            "  |",
+            "1 | vec3 light() {",
+            "2 |     return colour;",
+            "  |            ^",
+            "3 | }",
+            "",
+        ].join("\n"));
+    }
+}
@@ -0,0 +1,197 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::shader::{SetUniform, UniformValue};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShaderCall {
+    Bind,
+    SetUniform { name: String, value: UniformValue },
+    BindUniformBlock { block_name: String, binding: u32 },
+}
+
+// Every call made on a NullableShaderProgram, in order. Clones share the same history, so keep one and give
+// the other to the program under test with NullableShaderProgram::with_history.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderHistory {
+    calls: Rc<RefCell<Vec<ShaderCall>>>,
+}
+
+impl ShaderHistory {
+    pub fn new() -> ShaderHistory {
+        ShaderHistory { calls: Rc::new(RefCell::new(vec![])) }
+    }
+
+    pub(crate) fn record(&self, call: ShaderCall) {
+        self.calls.borrow_mut().push(call);
+    }
+
+    pub fn get_calls(&self) -> Vec<ShaderCall> {
+        self.calls.borrow().clone()
+    }
+
+    pub fn clear(&self) { // e.g. between frames
+        self.calls.borrow_mut().clear();
+    }
+
+    pub fn get_uniform(&self, name: &str) -> Option<UniformValue> { // the last value written
+        self.get_uniform_writes(name).pop()
+    }
+
+    pub fn get_uniform_writes(&self, name: &str) -> Vec<UniformValue> {
+        self.calls.borrow().iter()
+            .filter_map(|call| match call {
+                ShaderCall::SetUniform { name: written, value } if written == name => Some(value.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn get_bind_count(&self) -> usize {
+        self.calls.borrow().iter().filter(|call| **call == ShaderCall::Bind).count()
+    }
+
+    fn describe(&self) -> String { // the whole history, one call per line, for assertion failures
+        self.calls.borrow().iter().map(|call| format!("  {:?}", call)).collect::<Vec<String>>().join("\n")
+    }
+
+    #[track_caller]
+    pub fn assert_uniform(&self, name: &str, expected: &dyn SetUniform) {
+        let expected = expected.to_uniform_value();
+        match self.get_uniform(name) {
+            Some(value) if value == expected => {},
+            Some(value) => panic!("Uniform {} was last set to {} but expected {}, history:\n{}", name, value, expected, self.describe()),
+            None => panic!("Uniform {} was never set, expected {}, history:\n{}", name, expected, self.describe()),
+        }
+    }
+
+    #[track_caller]
+    pub fn assert_uniform_approx(&self, name: &str, expected: &dyn SetUniform, epsilon: f32) { // for values that went through float maths
+        let expected = expected.to_uniform_value();
+        match self.get_uniform(name) {
+            Some(value) if approx_eq(&value, &expected, epsilon) => {},
+            Some(value) => panic!("Uniform {} was last set to {} but expected {} (within {}), history:\n{}", name, value, expected, epsilon, self.describe()),
+            None => panic!("Uniform {} was never set, expected {}, history:\n{}", name, expected, self.describe()),
+        }
+    }
+
+    #[track_caller]
+    pub fn assert_not_set(&self, name: &str) {
+        if let Some(value) = self.get_uniform(name) {
+            panic!("Uniform {} was set to {} but expected it not to be, history:\n{}", name, value, self.describe());
+        }
+    }
+
+    #[track_caller]
+    pub fn assert_bound(&self) {
+        if self.get_bind_count() == 0 {
+            panic!("Shader program was never bound, history:\n{}", self.describe());
+        }
+    }
+
+    #[track_caller]
+    pub fn assert_set_after_bind(&self, name: &str) { // the last write to the uniform happened with the program bound
+        let calls = self.calls.borrow();
+        let last_write = calls.iter().rposition(|call| matches!(call, ShaderCall::SetUniform { name: written, .. } if written == name));
+        let first_bind = calls.iter().position(|call| *call == ShaderCall::Bind);
+
+        match (first_bind, last_write) {
+            (Some(bind), Some(write)) if bind < write => {},
+            (_, None) => panic!("Uniform {} was never set, history:\n{}", name, self.describe()),
+            _ => panic!("Uniform {} was set before the program was bound, history:\n{}", name, self.describe()),
+        }
+    }
+}
+
+fn approx_eq(value: &UniformValue, expected: &UniformValue, epsilon: f32) -> bool {
+    let floats = |value: &UniformValue| -> Option<Vec<f32>> {
+        match value {
+            UniformValue::Float(value) => Some(vec![*value]),
+            UniformValue::Vec2(value) => Some(value.as_array().to_vec()),
+            UniformValue::Vec3(value) => Some(value.as_array().to_vec()),
+            UniformValue::Vec4(value) => Some(value.as_array().to_vec()),
+            UniformValue::Mat3(value) => Some(value.as_array().iter().flat_map(|column| column.as_array()).collect()),
+            UniformValue::Mat4(value) => Some(value.as_array().iter().flat_map(|column| column.as_array()).collect()),
+            _ => None,
+        }
+    };
+
+    match (value, expected) {
+        (UniformValue::Array(values), UniformValue::Array(expected)) => values.len() == expected.len() && values.iter().zip(expected).all(|(value, expected)| approx_eq(value, expected, epsilon)),
+        _ => match (floats(value), floats(expected)) {
+            (Some(values), Some(expected_values)) if std::mem::discriminant(value) == std::mem::discriminant(expected) => values.iter().zip(&expected_values).all(|(value, expected)| (value - expected).abs() <= epsilon),
+            _ => value == expected,
+        },
+    }
+}
+
+
+#[cfg(test)]
+mod shader_history_tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+    use crate::shader::{NullableShaderProgram, ShaderCall, ShaderHistory, ShaderProgram, UniformValue};
+    use crate::types::{vec3, Mat4};
+
+    fn shader_program(history: &ShaderHistory) -> NullableShaderProgram {
+        NullableShaderProgram::new(Rc::new(RefCell::new(HashMap::new())), Rc::new(RefCell::new(false))).with_history(history.clone())
+    }
+
+    #[test]
+    fn records_typed_calls_in_order_test() {
+        let history = ShaderHistory::new();
+        let mut shader_program = shader_program(&history);
+
+        shader_program.bind();
+        shader_program.set_uniform("light_colour".to_string(), &vec3(1.0, 0.5, 0.25));
+        shader_program.set_uniform("cascades".to_string(), &[1.0_f32, 4.0]);
+        shader_program.bind_uniform_block("Camera", 2).unwrap();
+
+        assert_eq!(history.get_calls(), vec![
+            ShaderCall::Bind,
+            ShaderCall::SetUniform { name: "light_colour".to_string(), value: UniformValue::Vec3(vec3(1.0, 0.5, 0.25)) },
+            ShaderCall::SetUniform { name: "cascades".to_string(), value: UniformValue::Array(vec![UniformValue::Float(1.0), UniformValue::Float(4.0)]) },
+            ShaderCall::BindUniformBlock { block_name: "Camera".to_string(), binding: 2 },
+        ]);
+    }
+
+    #[test]
+    fn assertion_helpers_test() {
+        let history = ShaderHistory::new();
+        let mut shader_program = shader_program(&history);
+
+        shader_program.set_uniform("model".to_string(), &Mat4::identity());
+        shader_program.bind();
+        shader_program.set_uniform("exposure".to_string(), &0.5_f32);
+        shader_program.set_uniform("exposure".to_string(), &(0.1_f32 + 0.2));
+
+        history.assert_bound();
+        history.assert_uniform("model", &Mat4::identity());
+        history.assert_uniform_approx("exposure", &0.3_f32, 1e-6);
+        history.assert_set_after_bind("exposure");
+        history.assert_not_set("fog_colour");
+        assert_eq!(history.get_uniform_writes("exposure").len(), 2);
+        assert_eq!(history.get_bind_count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "set before the program was bound")]
+    fn set_before_bind_test() {
+        let history = ShaderHistory::new();
+        let mut shader_program = shader_program(&history);
+
+        shader_program.set_uniform("model".to_string(), &Mat4::identity());
+        shader_program.bind();
+
+        history.assert_set_after_bind("model");
+    }
+
+    #[test]
+    #[should_panic(expected = "was last set to 2 but expected 3")]
+    fn assert_uniform_reports_value_test() {
+        let history = ShaderHistory::new();
+        shader_program(&history).set_uniform("samples".to_string(), &2);
+
+        history.assert_uniform("samples", &3);
+    }
+}
@@ -1,24 +1,315 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use super::ShaderProgram;
+use super::{BuiltinShader, GLShaderProgram, ProgramBinaryCache, ShaderPreprocessor, ShaderProgram, ShaderStages};
+use super::shader_manifest::{parse_shader_manifest, ShaderDefinition};
 use crate::RenderError;
 
+type ShaderLoader = Box<dyn Fn() -> Result<Box<dyn ShaderProgram>, RenderError>>;
+type VariantLoader = Box<dyn Fn(&[String]) -> Result<Box<dyn ShaderProgram>, RenderError>>; // given the enabled keywords to #define
+type ReloadErrorCallback = Box<dyn FnMut(&str, &RenderError)>;
+
+struct WatchedFiles {
+    root_paths: Vec<PathBuf>, // the files the loader reads itself
+    source_paths: Vec<PathBuf>, // the root paths plus everything they #include
+    modified_times: Vec<Option<SystemTime>>, // None when the file couldn't be read, e.g. mid-save
+}
+
+impl WatchedFiles {
+    fn new(root_paths: Vec<PathBuf>, preprocessor: &ShaderPreprocessor) -> WatchedFiles {
+        let source_paths = preprocessor.collect_dependencies(&root_paths);
+        let modified_times = Self::read_modified_times(&source_paths);
+
+        WatchedFiles { root_paths, source_paths, modified_times }
+    }
+
+    fn read_modified_times(source_paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+        source_paths.iter()
+            .map(|path| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+            .collect()
+    }
+
+    fn check_for_changes(&mut self, preprocessor: &ShaderPreprocessor) -> bool {
+        if Self::read_modified_times(&self.source_paths) == self.modified_times {
+            return false;
+        }
+
+        self.source_paths = preprocessor.collect_dependencies(&self.root_paths); // the set of #includes may have changed too
+        self.modified_times = Self::read_modified_times(&self.source_paths); // a broken save isn't retried until the file changes again
+        true
+    }
+}
+
+struct WatchedShader {
+    files: WatchedFiles,
+    loader: ShaderLoader,
+}
+
+struct ShaderVariants { // one shader compiled with different sets of keywords #defined
+    keywords: BTreeSet<String>, // every keyword a variant may enable
+    files: Option<WatchedFiles>,
+    loader: VariantLoader,
+    compiled: HashMap<Vec<String>, Box<dyn ShaderProgram>>, // keyed by the sorted enabled keywords
+}
+
+impl ShaderVariants {
+    fn variant_key(&self, shader_name: &str, keywords: &[&str]) -> Result<Vec<String>, RenderError> {
+        let mut key = BTreeSet::new();
+
+        for keyword in keywords {
+            if !self.keywords.contains(*keyword) {
+                return Err(RenderError::ShaderError { shader_name: shader_name.to_string(), shader_type: "SHADER_VARIANT".to_string(), error: format!("Unknown shader keyword {}", keyword) });
+            }
+            key.insert(keyword.to_string());
+        }
+
+        Ok(key.into_iter().collect())
+    }
+
+    fn variant_name(shader_name: &str, key: &[String]) -> String {
+        format!("{}[{}]", shader_name, key.join(","))
+    }
+}
+
 pub struct ShaderManager {
-    shader_map: HashMap<String, Box<dyn ShaderProgram>>
+    shader_map: HashMap<String, Box<dyn ShaderProgram>>,
+    shader_variants: HashMap<String, ShaderVariants>,
+    watched_shaders: HashMap<String, WatchedShader>,
+    reload_error_callback: Option<ReloadErrorCallback>,
+    preprocessor: ShaderPreprocessor,
+    uniform_block_bindings: HashMap<String, u32>, // applied to every program that has the block, including ones loaded later
+    program_binary_cache: Option<ProgramBinaryCache>,
 }
 
 
 impl ShaderManager {
     pub fn new () -> ShaderManager {
-        ShaderManager { shader_map: HashMap::new() }
+        ShaderManager { shader_map: HashMap::new(), shader_variants: HashMap::new(), watched_shaders: HashMap::new(), reload_error_callback: None, preprocessor: ShaderPreprocessor::new(), uniform_block_bindings: HashMap::new(), program_binary_cache: None }
     }
 
-    pub fn register_shader(&mut self, name: String, shader: Box<dyn ShaderProgram>) -> Result<&mut Box<dyn ShaderProgram>, RenderError> {
+    pub fn register_shader(&mut self, name: String, mut shader: Box<dyn ShaderProgram>) -> Result<&mut Box<dyn ShaderProgram>, RenderError> {
+        Self::apply_uniform_block_bindings(&self.uniform_block_bindings, &mut shader);
+
         match self.shader_map.insert(name.clone(), shader) {
             Some(_) => Err(RenderError::ShaderError { shader_name: name, shader_type: "SHADER_PROGRAM".to_string(), error: "Shader already exists in shader manager!".to_string() }),
             None => Ok(self.shader_map.get_mut(&name).unwrap())
         }
-    
+
+    }
+
+    pub fn load_shader_program(&mut self, name: String, filepath: &str, geometry_included: bool) -> Result<&mut Box<dyn ShaderProgram>, RenderError> { // loads from disk and watches the files for hot reloading
+        let filepath = filepath.to_string();
+        let loader_name = name.clone();
+        let preprocessor = self.preprocessor.clone();
+        let cache = self.program_binary_cache.clone();
+        let stages = GLShaderProgram::file_stages(&filepath, geometry_included);
+
+        self.register_watched_shader(
+            name,
+            stages.get_source_paths(),
+            Box::new(move || Ok(Box::new(GLShaderProgram::load_from_stages_cached(&loader_name, &stages, &preprocessor, &[], cache.as_ref())?) as Box<dyn ShaderProgram>)),
+        )
+    }
+
+    pub fn load_shader_stages(&mut self, name: String, stages: ShaderStages) -> Result<&mut Box<dyn ShaderProgram>, RenderError> { // any combination of stages, the files among them are watched for hot reloading
+        let loader_name = name.clone();
+        let preprocessor = self.preprocessor.clone();
+        let cache = self.program_binary_cache.clone();
+
+        self.register_watched_shader(
+            name,
+            stages.get_source_paths(),
+            Box::new(move || Ok(Box::new(GLShaderProgram::load_from_stages_cached(&loader_name, &stages, &preprocessor, &[], cache.as_ref())?) as Box<dyn ShaderProgram>)),
+        )
+    }
+
+    // Loads every program listed in {graphics_base_path}/shaders.toml, with stage files relative to {graphics_base_path}/shaders.
+    // Programs that load are registered and watched even if others fail, the failures are returned together.
+    pub fn load_shaders_from_assets_folder(&mut self, graphics_base_path: &str) -> Result<(), RenderError> {
+        let shader_definitions_file = format!("{}/shaders.toml", graphics_base_path);
+        let shader_folder = Path::new(graphics_base_path).join("shaders");
+
+        let shader_toml_string = match std::fs::read_to_string(shader_definitions_file.clone()) {
+            Ok(toml_string) => toml_string,
+            Err(e) => return Err(RenderError::ShaderManifestError { manifest_path: shader_definitions_file, errors: vec![RenderError::ShaderError { shader_name: "shaders.toml".to_string(), shader_type: "MANIFEST".to_string(), error: e.to_string() }] }),
+        };
+
+        let definitions = match parse_shader_manifest(&shader_toml_string) {
+            Ok(definitions) => definitions,
+            Err(e) => return Err(RenderError::ShaderManifestError { manifest_path: shader_definitions_file, errors: vec![RenderError::ShaderError { shader_name: "shaders.toml".to_string(), shader_type: "MANIFEST".to_string(), error: e }] }),
+        };
+
+        let mut errors = vec![];
+        for (name, definition) in definitions {
+            let result = match definition {
+                Ok(definition) => self.load_shader_definition(name.clone(), &definition, &shader_folder).map(|_| ()),
+                Err(e) => Err(RenderError::ShaderError { shader_name: name, shader_type: "MANIFEST".to_string(), error: e }),
+            };
+
+            if let Err(e) = result {
+                errors.push(e);
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(RenderError::ShaderManifestError { manifest_path: shader_definitions_file, errors }),
+        }
+    }
+
+    fn load_shader_definition(&mut self, name: String, definition: &ShaderDefinition, shader_folder: &Path) -> Result<&mut Box<dyn ShaderProgram>, RenderError> {
+        let default_uniforms = definition.default_uniforms()
+            .map_err(|e| RenderError::ShaderError { shader_name: name.clone(), shader_type: "MANIFEST".to_string(), error: e })?;
+        let stages = definition.stages(shader_folder);
+        let root_paths = stages.get_source_paths();
+        let defines = definition.defines.clone();
+        let preprocessor = self.preprocessor.clone();
+        let cache = self.program_binary_cache.clone();
+        let loader_name = name.clone();
+
+        self.register_watched_shader(name, root_paths, Box::new(move || {
+            let mut shader: Box<dyn ShaderProgram> = Box::new(GLShaderProgram::load_from_stages_cached(&loader_name, &stages, &preprocessor, &defines, cache.as_ref())?);
+            for (uniform_name, value) in &default_uniforms { // set as part of loading so hot reloads get them too
+                shader.set_uniform(uniform_name.clone(), value.as_ref());
+            }
+
+            Ok(shader)
+        }))
+    }
+
+    pub fn register_watched_shader(&mut self, name: String, root_paths: Vec<PathBuf>, loader: ShaderLoader) -> Result<&mut Box<dyn ShaderProgram>, RenderError> { // the loader is called again whenever one of the source files or their includes change
+        let files = WatchedFiles::new(root_paths, &self.preprocessor); // read before loading so a save during the load is still picked up
+        let shader = loader()?;
+
+        self.register_shader(name.clone(), shader)?;
+        self.watched_shaders.insert(name.clone(), WatchedShader { files, loader });
+
+        Ok(self.shader_map.get_mut(&name).unwrap())
+    }
+
+    pub fn load_shader_variants(&mut self, name: String, filepath: &str, geometry_included: bool, keywords: &[&str]) -> Result<(), RenderError> { // nothing is compiled until a variant is first asked for
+        let loader_name = name.clone();
+        let preprocessor = self.preprocessor.clone();
+        let cache = self.program_binary_cache.clone();
+        let stages = GLShaderProgram::file_stages(filepath, geometry_included);
+
+        self.register_shader_variants(name, Some(stages.get_source_paths()), keywords, Box::new(move |defines| {
+            let variant_name = ShaderVariants::variant_name(&loader_name, defines);
+            Ok(Box::new(GLShaderProgram::load_from_stages_cached(&variant_name, &stages, &preprocessor, defines, cache.as_ref())?) as Box<dyn ShaderProgram>)
+        }))
+    }
+
+    pub fn register_shader_variants(&mut self, name: String, root_paths: Option<Vec<PathBuf>>, keywords: &[&str], loader: VariantLoader) -> Result<(), RenderError> { // root_paths are watched for hot reloading, if given
+        if self.shader_map.contains_key(&name) || self.shader_variants.contains_key(&name) {
+            return Err(RenderError::ShaderError { shader_name: name, shader_type: "SHADER_VARIANT".to_string(), error: "Shader already exists in shader manager!".to_string() });
+        }
+
+        let files = root_paths.map(|root_paths| WatchedFiles::new(root_paths, &self.preprocessor));
+        let keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+        self.shader_variants.insert(name, ShaderVariants { keywords, files, loader, compiled: HashMap::new() });
+
+        Ok(())
+    }
+
+    pub fn set_program_binary_cache(&mut self, cache: Option<ProgramBinaryCache>) { // used by programs loaded after this, e.g. ProgramBinaryCache::new(Path::new("cache/shaders"))
+        self.program_binary_cache = cache;
+    }
+
+    pub fn get_program_binary_cache(&self) -> Option<&ProgramBinaryCache> { // for its hit/miss statistics
+        self.program_binary_cache.as_ref()
+    }
+
+    pub fn set_uniform_block_binding(&mut self, block_name: &str, binding: u32) { // e.g. a "Camera" block shared by every shader through one UniformBuffer
+        self.uniform_block_bindings.insert(block_name.to_string(), binding);
+
+        let bindings = HashMap::from([(block_name.to_string(), binding)]);
+        let shaders = self.shader_map.values_mut()
+            .chain(self.shader_variants.values_mut().flat_map(|variants| variants.compiled.values_mut()));
+        for shader in shaders {
+            Self::apply_uniform_block_bindings(&bindings, shader);
+        }
+    }
+
+    fn apply_uniform_block_bindings(bindings: &HashMap<String, u32>, shader: &mut Box<dyn ShaderProgram>) {
+        for (block_name, binding) in bindings {
+            let has_block = match shader.get_reflection() {
+                Some(reflection) => reflection.get_uniform_block(block_name).is_some(),
+                None => true, // can't tell, so let the program decide
+            };
+
+            if has_block {
+                let _ = shader.bind_uniform_block(block_name, *binding); // programs without the block just don't use it
+            }
+        }
+    }
+
+    pub fn set_reload_error_callback<F: FnMut(&str, &RenderError) + 'static>(&mut self, callback: F) {
+        self.reload_error_callback = Some(Box::new(callback));
+    }
+
+    pub fn get_preprocessor_mut(&mut self) -> &mut ShaderPreprocessor { // add library include paths here before loading any shaders that use them
+        &mut self.preprocessor
+    }
+
+    pub fn get_source_paths(&self, shader_name: &str) -> Option<&[PathBuf]> {
+        match self.watched_shaders.get(shader_name) {
+            Some(watched) => Some(watched.files.source_paths.as_slice()),
+            None => self.shader_variants.get(shader_name)?.files.as_ref().map(|files| files.source_paths.as_slice()),
+        }
+    }
+
+    // Checks the modification times of every watched file and reloads the programs that changed.
+    // Returns the names of the programs that were replaced, their uniforms will need setting again.
+    // A program that fails to compile keeps its previous version and the error goes to the callback.
+    // Variants are reported as NAME[KEYWORD,KEYWORD].
+    pub fn reload_changed_shaders(&mut self) -> Vec<String> {
+        let mut reloaded = vec![];
+        let mut errors = vec![];
+
+        for (name, watched) in self.watched_shaders.iter_mut() {
+            if !watched.files.check_for_changes(&self.preprocessor) {
+                continue;
+            }
+
+            match (watched.loader)() {
+                Ok(mut shader) => {
+                    Self::apply_uniform_block_bindings(&self.uniform_block_bindings, &mut shader);
+                    self.shader_map.insert(name.clone(), shader);
+                    reloaded.push(name.clone());
+                },
+                Err(e) => errors.push((name.clone(), e)),
+            }
+        }
+
+        for (name, variants) in self.shader_variants.iter_mut() {
+            let changed = match &mut variants.files {
+                Some(files) => files.check_for_changes(&self.preprocessor),
+                None => false,
+            };
+            if !changed {
+                continue;
+            }
+
+            for (key, shader) in variants.compiled.iter_mut() { // only variants already in use are rebuilt, the rest compile fresh when asked for
+                match (variants.loader)(key) {
+                    Ok(mut new_shader) => {
+                        Self::apply_uniform_block_bindings(&self.uniform_block_bindings, &mut new_shader);
+                        *shader = new_shader;
+                        reloaded.push(ShaderVariants::variant_name(name, key));
+                    },
+                    Err(e) => errors.push((ShaderVariants::variant_name(name, key), e)),
+                }
+            }
+        }
+
+        if let Some(callback) = &mut self.reload_error_callback {
+            for (name, error) in errors {
+                callback(&name, &error);
+            }
+        }
+
+        reloaded
     }
 
     pub fn bind(&mut self, shader_name: String) -> Result<&mut Box<dyn ShaderProgram>, RenderError>{
@@ -28,7 +319,44 @@ impl ShaderManager {
                 Ok(shader)
             },
             Err(e) => Err(e)
-        }      
+        }
+    }
+
+    pub fn get_builtin_shader(&mut self, builtin: BuiltinShader) -> Result<&mut Box<dyn ShaderProgram>, RenderError> { // compiles the embedded program the first time, unless one was already registered under its name
+        let name = builtin.get_name().to_string();
+
+        if !self.shader_map.contains_key(&name) {
+            let mut shader: Box<dyn ShaderProgram> = Box::new(GLShaderProgram::load_from_stages_cached(&name, &builtin.get_stages(), &self.preprocessor, &[], self.program_binary_cache.as_ref())?);
+            for (uniform_name, value) in builtin.default_uniforms() {
+                shader.set_uniform(uniform_name.to_string(), value.as_ref());
+            }
+
+            self.register_shader(name.clone(), shader)?;
+        }
+
+        self.get_shader(name)
+    }
+
+    pub fn bind_builtin(&mut self, builtin: BuiltinShader) -> Result<&mut Box<dyn ShaderProgram>, RenderError> {
+        let shader = self.get_builtin_shader(builtin)?;
+        shader.bind();
+
+        Ok(shader)
+    }
+
+    pub fn register_builtin_shaders(&mut self) -> Result<(), RenderError> { // compiles them all up front, e.g. so any driver problems show up at startup
+        for builtin in BuiltinShader::ALL {
+            self.get_builtin_shader(builtin)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn bind_variant(&mut self, shader_name: &str, keywords: &[&str]) -> Result<&mut Box<dyn ShaderProgram>, RenderError> {
+        let shader = self.get_shader_variant(shader_name, keywords)?;
+        shader.bind();
+
+        Ok(shader)
     }
 
     pub fn get_shader(&mut self, shader_name: String) -> Result<&mut Box<dyn ShaderProgram>, RenderError> {
@@ -37,4 +365,162 @@ impl ShaderManager {
             None => Err(RenderError::ShaderError { shader_name, shader_type: "SHADER_PROGRAM".to_string(), error: "Shader doesn't exist!".to_string() })
         }
     }
-}
\ No newline at end of file
+
+    pub fn get_shader_variant(&mut self, shader_name: &str, keywords: &[&str]) -> Result<&mut Box<dyn ShaderProgram>, RenderError> { // compiles the variant the first time it's asked for, keyword order doesn't matter
+        let variants = match self.shader_variants.get_mut(shader_name) {
+            Some(variants) => variants,
+            None => return Err(RenderError::ShaderError { shader_name: shader_name.to_string(), shader_type: "SHADER_VARIANT".to_string(), error: "Shader doesn't exist!".to_string() }),
+        };
+
+        let key = variants.variant_key(shader_name, keywords)?;
+        if !variants.compiled.contains_key(&key) {
+            let mut shader = (variants.loader)(&key)?;
+            Self::apply_uniform_block_bindings(&self.uniform_block_bindings, &mut shader);
+            variants.compiled.insert(key.clone(), shader);
+        }
+
+        Ok(variants.compiled.get_mut(&key).unwrap())
+    }
+
+    pub fn get_compiled_variant_count(&self, shader_name: &str) -> usize {
+        self.shader_variants.get(shader_name).map(|variants| variants.compiled.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod shader_manager_tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+    use std::time::{Duration, SystemTime};
+    use crate::RenderError;
+    use crate::shader::{BuiltinShader, NullableShaderProgram, ShaderManager, ShaderProgram};
+
+    fn write_source(path: &Path, source: &str, seconds: u64) { // sets the modification time explicitly so the test doesn't depend on timestamp resolution
+        std::fs::write(path, source).unwrap();
+        File::options().write(true).open(path).unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)).unwrap();
+    }
+
+    fn watch(manager: &mut ShaderManager, path: &Path, uniform_values: Rc<RefCell<HashMap<String, String>>>) {
+        let loader_path = path.to_path_buf();
+
+        manager.register_watched_shader("TEST".to_string(), vec![path.to_path_buf()], Box::new(move || {
+            let source = std::fs::read_to_string(&loader_path).unwrap();
+            if source.contains("error") {
+                return Err(RenderError::ShaderError { shader_name: "TEST".to_string(), shader_type: "FRAGMENT".to_string(), error: source });
+            }
+
+            uniform_values.borrow_mut().insert("source".to_string(), source);
+            Ok(Box::new(NullableShaderProgram::new(uniform_values.clone(), Rc::new(RefCell::new(false)))) as Box<dyn ShaderProgram>)
+        })).unwrap();
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dec_gl_{}_{}.fsh", name, std::process::id()))
+    }
+
+    #[test]
+    fn reloads_changed_shader_test() {
+        let path = temp_path("reload");
+        write_source(&path, "version 1", 1);
+        let uniform_values = Rc::new(RefCell::new(HashMap::new()));
+        let mut manager = ShaderManager::new();
+        watch(&mut manager, &path, uniform_values.clone());
+
+        assert!(manager.reload_changed_shaders().is_empty());
+
+        write_source(&path, "version 2", 2);
+
+        assert_eq!(manager.reload_changed_shaders(), vec!["TEST".to_string()]);
+        assert_eq!(uniform_values.borrow().get("source").unwrap(), "version 2");
+        assert!(manager.reload_changed_shaders().is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn failed_reload_keeps_old_shader_test() {
+        let path = temp_path("failed_reload");
+        write_source(&path, "version 1", 1);
+        let errors = Rc::new(RefCell::new(vec![]));
+        let mut manager = ShaderManager::new();
+        watch(&mut manager, &path, Rc::new(RefCell::new(HashMap::new())));
+
+        let errors_handle = errors.clone();
+        manager.set_reload_error_callback(move |name, _| errors_handle.borrow_mut().push(name.to_string()));
+
+        write_source(&path, "syntax error", 2);
+
+        assert!(manager.reload_changed_shaders().is_empty());
+        assert_eq!(*errors.borrow(), vec!["TEST".to_string()]);
+        assert!(manager.get_shader("TEST".to_string()).is_ok());
+        assert_eq!(manager.get_source_paths("TEST").unwrap(), std::slice::from_ref(&path));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn register_variants(manager: &mut ShaderManager, loads: Rc<RefCell<Vec<Vec<String>>>>) {
+        manager.register_shader_variants("LIT".to_string(), None, &["SKINNED", "SHADOWS", "HAS_NORMAL_MAP"], Box::new(move |defines| {
+            loads.borrow_mut().push(defines.to_vec());
+            Ok(Box::new(NullableShaderProgram::new(Rc::new(RefCell::new(HashMap::new())), Rc::new(RefCell::new(false)))) as Box<dyn ShaderProgram>)
+        })).unwrap();
+    }
+
+    #[test]
+    fn variants_compile_lazily_and_are_cached_test() {
+        let loads = Rc::new(RefCell::new(vec![]));
+        let mut manager = ShaderManager::new();
+        register_variants(&mut manager, loads.clone());
+
+        assert!(loads.borrow().is_empty());
+
+        manager.bind_variant("LIT", &["SKINNED", "SHADOWS"]).unwrap();
+        manager.bind_variant("LIT", &["SHADOWS", "SKINNED"]).unwrap();
+        manager.bind_variant("LIT", &[]).unwrap();
+
+        assert_eq!(*loads.borrow(), vec![vec!["SHADOWS".to_string(), "SKINNED".to_string()], vec![]]);
+        assert_eq!(manager.get_compiled_variant_count("LIT"), 2);
+    }
+
+    #[test]
+    fn unknown_variant_keyword_test() {
+        let mut manager = ShaderManager::new();
+        register_variants(&mut manager, Rc::new(RefCell::new(vec![])));
+
+        assert!(manager.bind_variant("LIT", &["WIREFRAME"]).is_err());
+        assert!(manager.bind_variant("UNLIT", &[]).is_err());
+        assert!(manager.register_shader_variants("LIT".to_string(), None, &[], Box::new(|_| unreachable!())).is_err());
+    }
+
+    #[test]
+    fn uniform_block_bindings_apply_to_new_and_existing_shaders_test() {
+        let existing_bindings = Rc::new(RefCell::new(HashMap::new()));
+        let later_bindings = Rc::new(RefCell::new(HashMap::new()));
+        let mut manager = ShaderManager::new();
+
+        let existing = NullableShaderProgram::new(Rc::new(RefCell::new(HashMap::new())), Rc::new(RefCell::new(false))).with_block_bindings(existing_bindings.clone());
+        manager.register_shader("EXISTING".to_string(), Box::new(existing)).unwrap();
+
+        manager.set_uniform_block_binding("Camera", 0);
+
+        let later = NullableShaderProgram::new(Rc::new(RefCell::new(HashMap::new())), Rc::new(RefCell::new(false))).with_block_bindings(later_bindings.clone());
+        manager.register_shader("LATER".to_string(), Box::new(later)).unwrap();
+
+        assert_eq!(existing_bindings.borrow().get("Camera"), Some(&0));
+        assert_eq!(later_bindings.borrow().get("Camera"), Some(&0));
+    }
+
+    #[test]
+    fn registered_shader_replaces_builtin_test() {
+        let uniform_values = Rc::new(RefCell::new(HashMap::new()));
+        let mut manager = ShaderManager::new();
+        manager.register_shader("BLOOM".to_string(), Box::new(NullableShaderProgram::new(uniform_values.clone(), Rc::new(RefCell::new(false))))).unwrap();
+
+        manager.bind_builtin(BuiltinShader::BloomBlur).unwrap().set_uniform("direction".to_string(), &1);
+
+        assert_eq!(uniform_values.borrow().get("direction"), Some(&"1".to_string())); // never compiled the embedded one
+    }
+}
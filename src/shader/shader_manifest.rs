@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use serde::Deserialize;
+use crate::shader::{SetUniform, ShaderStage, ShaderStages, UniformValue};
+use crate::types::{ivec2, mat4, vec2, vec3, vec4};
+
+type DefaultUniforms = Vec<(String, Box<dyn SetUniform>)>;
+type ManifestEntries = Vec<(String, Result<ShaderDefinition, String>)>;
+
+// One entry of shaders.toml, e.g.
+//
+// [LIT]
+// vertex = "lit.vsh"
+// fragment = "lit.fsh"
+// defines = ["SHADOWS", "MAX_LIGHTS 4"]
+// uniforms = { u_tint = [1.0, 1.0, 1.0, 1.0], u_shadow_samples = 4 }
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ShaderDefinition {
+    pub vertex: String,
+    pub fragment: String,
+    pub geometry: Option<String>,
+    pub tess_control: Option<String>,
+    pub tess_evaluation: Option<String>,
+    #[serde(default)]
+    pub defines: Vec<String>,
+    #[serde(default)]
+    pub uniforms: BTreeMap<String, toml::Value>,
+}
+
+impl ShaderDefinition {
+    pub fn stages(&self, shader_folder: &Path) -> ShaderStages {
+        [
+            (ShaderStage::Vertex, Some(&self.vertex)),
+            (ShaderStage::TessControl, self.tess_control.as_ref()),
+            (ShaderStage::TessEvaluation, self.tess_evaluation.as_ref()),
+            (ShaderStage::Geometry, self.geometry.as_ref()),
+            (ShaderStage::Fragment, Some(&self.fragment)),
+        ].into_iter()
+            .filter_map(|(stage, file)| file.map(|file| (stage, shader_folder.join(file))))
+            .fold(ShaderStages::new(), |stages, (stage, path)| stages.with_file(stage, path))
+    }
+
+    pub fn default_uniforms(&self) -> Result<DefaultUniforms, String> {
+        self.uniforms.iter()
+            .map(|(name, value)| uniform_from_toml(value).map(|uniform| (name.clone(), Box::new(uniform) as Box<dyn SetUniform>)).map_err(|e| format!("uniform {}: {}", name, e)))
+            .collect()
+    }
+}
+
+pub(crate) fn parse_shader_manifest(toml_string: &str) -> Result<ManifestEntries, String> { // entries are parsed separately so one bad program doesn't hide the others
+    let table: HashMap<String, toml::Value> = toml::from_str(toml_string).map_err(|e| e.to_string())?;
+    let table: BTreeMap<String, toml::Value> = table.into_iter().collect(); // sorted so errors come out in a stable order
+
+    Ok(table.into_iter()
+        .map(|(name, value)| {
+            let definition = value.try_into::<ShaderDefinition>().map_err(|e| e.to_string());
+            (name, definition)
+        })
+        .collect())
+}
+
+pub(crate) fn uniform_from_toml(value: &toml::Value) -> Result<UniformValue, String> { // floats become f32/vecN/mat4, integers i32/ivec2 and booleans 0 or 1
+    match value {
+        toml::Value::Float(value) => Ok(UniformValue::Float(*value as f32)),
+        toml::Value::Integer(value) => Ok(UniformValue::Int(*value as i32)),
+        toml::Value::Boolean(value) => Ok(UniformValue::Int(*value as i32)),
+        toml::Value::Array(values) => {
+            if values.len() == 2 && values.iter().all(|value| value.is_integer()) {
+                return Ok(UniformValue::IVec2(ivec2(values[0].as_integer().unwrap() as i32, values[1].as_integer().unwrap() as i32)));
+            }
+
+            let floats: Vec<f32> = values.iter()
+                .map(|value| value.as_float().or_else(|| value.as_integer().map(|value| value as f64)).map(|value| value as f32))
+                .collect::<Option<Vec<f32>>>()
+                .ok_or_else(|| "arrays can only hold numbers".to_string())?;
+
+            match floats.len() {
+                2 => Ok(UniformValue::Vec2(vec2(floats[0], floats[1]))),
+                3 => Ok(UniformValue::Vec3(vec3(floats[0], floats[1], floats[2]))),
+                4 => Ok(UniformValue::Vec4(vec4(floats[0], floats[1], floats[2], floats[3]))),
+                16 => Ok(UniformValue::Mat4(mat4( // column major, like GLSL
+                    vec4(floats[0], floats[1], floats[2], floats[3]),
+                    vec4(floats[4], floats[5], floats[6], floats[7]),
+                    vec4(floats[8], floats[9], floats[10], floats[11]),
+                    vec4(floats[12], floats[13], floats[14], floats[15]),
+                ))),
+                length => Err(format!("arrays of {} numbers aren't a supported uniform type", length)),
+            }
+        },
+        value => Err(format!("{} values aren't a supported uniform type", value.type_str())),
+    }
+}
+
+
+#[cfg(test)]
+mod shader_manifest_tests {
+    use std::path::{Path, PathBuf};
+    use crate::shader::shader_manifest::parse_shader_manifest;
+    use crate::shader::ShaderStage;
+
+    const MANIFEST: &str = r#"
+        [TERRAIN]
+        vertex = "terrain.vsh"
+        tess_control = "terrain.tcs"
+        tess_evaluation = "terrain.tes"
+        fragment = "terrain.fsh"
+        defines = ["SHADOWS"]
+        uniforms = { u_tint = [1.0, 0.5, 0.25, 1], u_levels = 4, u_offset = [2, 3], u_wireframe = false }
+
+        [BROKEN]
+        vertex = "broken.vsh"
+
+        [BAD_UNIFORM]
+        vertex = "a.vsh"
+        fragment = "a.fsh"
+        uniforms = { u_name = "text" }
+    "#;
+
+    #[test]
+    fn parses_stages_in_pipeline_order_test() {
+        let entries = parse_shader_manifest(MANIFEST).unwrap();
+        let (_, terrain) = entries.iter().find(|(name, _)| name == "TERRAIN").unwrap();
+        let terrain = terrain.as_ref().unwrap();
+
+        let stages = terrain.stages(Path::new("shaders"));
+
+        assert_eq!(stages.get_stages().map(|(stage, _)| stage).collect::<Vec<ShaderStage>>(), vec![ShaderStage::Vertex, ShaderStage::TessControl, ShaderStage::TessEvaluation, ShaderStage::Fragment]);
+        assert_eq!(stages.get_source_paths(), vec![
+            PathBuf::from("shaders/terrain.vsh"),
+            PathBuf::from("shaders/terrain.tcs"),
+            PathBuf::from("shaders/terrain.tes"),
+            PathBuf::from("shaders/terrain.fsh"),
+        ]);
+        assert_eq!(terrain.defines, vec!["SHADOWS".to_string()]);
+    }
+
+    #[test]
+    fn converts_default_uniforms_test() {
+        let entries = parse_shader_manifest(MANIFEST).unwrap();
+        let (_, terrain) = entries.iter().find(|(name, _)| name == "TERRAIN").unwrap();
+
+        let uniforms: Vec<(String, String)> = terrain.as_ref().unwrap().default_uniforms().unwrap().into_iter()
+            .map(|(name, value)| (name, value.to_string()))
+            .collect();
+
+        assert_eq!(uniforms, vec![
+            ("u_levels".to_string(), "4".to_string()),
+            ("u_offset".to_string(), crate::types::ivec2(2, 3).to_string()),
+            ("u_tint".to_string(), crate::types::vec4(1.0, 0.5, 0.25, 1.0).to_string()),
+            ("u_wireframe".to_string(), "0".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn bad_entries_are_reported_individually_test() {
+        let entries = parse_shader_manifest(MANIFEST).unwrap();
+
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["BAD_UNIFORM", "BROKEN", "TERRAIN"]);
+
+        assert!(entries[1].1.is_err()); // no fragment stage
+        assert!(entries[0].1.as_ref().unwrap().default_uniforms().is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use crate::RenderError;
+
+type Result<T> = std::result::Result<T, RenderError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreprocessedSource {
+    pub source: String,
+    pub source_files: Vec<PathBuf>, // indexed by the source string number in the #line directives, the root file is always 0
+}
+
+impl PreprocessedSource {
+    pub fn get_source_file(&self, index: usize) -> Option<&Path> { // maps the file number in a compiler error back to a path
+        self.source_files.get(index).map(|path| path.as_path())
+    }
+}
+
+struct PreprocessState {
+    output: String,
+    source_files: Vec<PathBuf>,
+    include_stack: Vec<PathBuf>,
+    once_files: HashSet<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ShaderPreprocessor { // resolves #include "file" and #include <file> before the source is given to the driver
+    include_paths: Vec<PathBuf>, // library folders searched after the including file's own folder
+    virtual_files: HashMap<PathBuf, String>, // in-memory sources, checked before the disk
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> ShaderPreprocessor {
+        ShaderPreprocessor { include_paths: vec![], virtual_files: HashMap::new() }
+    }
+
+    pub fn add_include_path(&mut self, path: &Path) {
+        self.include_paths.push(normalise_path(path));
+    }
+
+    pub fn add_virtual_file(&mut self, path: &Path, source: &str) {
+        self.virtual_files.insert(normalise_path(path), source.to_string());
+    }
+
+    pub fn process_file(&self, path: &Path) -> Result<PreprocessedSource> {
+        let path = normalise_path(path);
+        let source = self.read_file(&path).map_err(|error| RenderError::ShaderIncludeError { shader_path: path.to_string_lossy().to_string(), line: 0, error })?;
+
+        self.process_source(&path, &source)
+    }
+
+    pub fn process_source(&self, path: &Path, source: &str) -> Result<PreprocessedSource> { // the path is only used to resolve relative includes and name the file
+        let mut state = PreprocessState { output: String::with_capacity(source.len()), source_files: vec![], include_stack: vec![], once_files: HashSet::new() };
+
+        self.process_into(&mut state, &normalise_path(path), source)?;
+
+        Ok(PreprocessedSource { source: state.output, source_files: state.source_files })
+    }
+
+    pub fn collect_dependencies(&self, paths: &[PathBuf]) -> Vec<PathBuf> { // every file the given sources pull in, including themselves; unreadable files are skipped
+        let mut dependencies: Vec<PathBuf> = vec![];
+
+        for path in paths {
+            match self.process_file(path) {
+                Ok(processed) => dependencies.extend(processed.source_files),
+                Err(_) => dependencies.push(normalise_path(path)), // still watch it so fixing the error triggers a reload
+            }
+        }
+
+        let mut seen = HashSet::new();
+        dependencies.retain(|path| seen.insert(path.clone()));
+        dependencies
+    }
+
+    fn process_into(&self, state: &mut PreprocessState, path: &Path, source: &str) -> Result<()> {
+        let file_index = match state.source_files.iter().position(|file| file == path) {
+            Some(index) => index,
+            None => {
+                state.source_files.push(path.to_path_buf());
+                state.source_files.len() - 1
+            }
+        };
+        let is_root = state.include_stack.is_empty();
+        state.include_stack.push(path.to_path_buf());
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            // every source line produces exactly one output line, so line numbers only need fixing around includes
+            match parse_directive(line) {
+                Some(("pragma", "once")) => {
+                    state.once_files.insert(path.to_path_buf());
+                    state.output.push('\n');
+                },
+                Some(("version", _)) if !is_root => state.output.push('\n'), // only the root file's #version is kept
+                Some(("include", argument)) => {
+                    let error = |error: String| RenderError::ShaderIncludeError { shader_path: path.to_string_lossy().to_string(), line: line_number, error };
+
+                    let (target, library_only) = parse_include_target(argument).ok_or_else(|| error(format!("Malformed #include {}", argument)))?;
+                    let include_path = self.resolve_include(path, target, library_only).ok_or_else(|| error(format!("Couldn't find include \"{}\"", target)))?;
+
+                    if state.once_files.contains(&include_path) { // already included and guarded with #pragma once
+                        state.output.push('\n');
+                        continue;
+                    }
+
+                    if state.include_stack.contains(&include_path) {
+                        let chain: Vec<String> = state.include_stack.iter()
+                            .chain(std::iter::once(&include_path))
+                            .map(|path| path.to_string_lossy().to_string())
+                            .collect();
+                        return Err(error(format!("Include cycle: {}", chain.join(" -> "))));
+                    }
+
+                    let include_source = self.read_file(&include_path).map_err(error)?;
+                    let include_index = state.source_files.iter().position(|file| *file == include_path).unwrap_or(state.source_files.len());
+
+                    state.output.push_str(&format!("#line 1 {}\n", include_index));
+                    self.process_into(state, &include_path, &include_source)?;
+                    state.output.push_str(&format!("#line {} {}\n", line_number + 1, file_index)); // back to the line after the #include
+                },
+                _ => {
+                    state.output.push_str(line);
+                    state.output.push('\n');
+                },
+            }
+        }
+
+        state.include_stack.pop();
+        Ok(())
+    }
+
+    fn resolve_include(&self, including_path: &Path, target: &str, library_only: bool) -> Option<PathBuf> {
+        let relative = if library_only { None } else { including_path.parent().map(|folder| folder.join(target)) };
+
+        relative.into_iter()
+            .chain(self.include_paths.iter().map(|folder| folder.join(target)))
+            .map(|candidate| normalise_path(&candidate))
+            .find(|candidate| self.virtual_files.contains_key(candidate) || candidate.is_file())
+    }
+
+    fn read_file(&self, path: &Path) -> std::result::Result<String, String> {
+        match self.virtual_files.get(path) {
+            Some(source) => Ok(source.clone()),
+            None => std::fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path.to_string_lossy(), e)),
+        }
+    }
+}
+
+
+pub fn inject_defines(source: &str, defines: &[String]) -> String { // each define is "NAME" or "NAME value"
+    if defines.is_empty() {
+        return source.to_string();
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    let version_index = lines.iter().position(|line| matches!(parse_directive(line), Some(("version", _)))); // #version has to stay the first statement
+    let split = version_index.map(|index| index + 1).unwrap_or(0);
+
+    let mut output = String::with_capacity(source.len() + defines.len() * 32);
+    for line in &lines[..split] {
+        output.push_str(line);
+        output.push('\n');
+    }
+    for define in defines {
+        output.push_str(&format!("#define {}\n", define));
+    }
+    output.push_str(&format!("#line {} 0\n", split + 1)); // so compiler errors still report the original line numbers
+    for line in &lines[split..] {
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    output
+}
+
+fn parse_directive(line: &str) -> Option<(&str, &str)> { // "  #  include "a.glsl"" -> ("include", "\"a.glsl\"")
+    let directive = line.trim_start().strip_prefix('#')?.trim_start();
+    let name_end = directive.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(directive.len());
+
+    Some((&directive[..name_end], directive[name_end..].trim()))
+}
+
+fn parse_include_target(argument: &str) -> Option<(&str, bool)> { // bool is whether only the library paths should be searched
+    let argument = argument.split("//").next()?.trim(); // allow a trailing comment
+
+    if let Some(target) = argument.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        Some((target, false))
+    }
+    else {
+        argument.strip_prefix('<').and_then(|rest| rest.strip_suffix('>')).map(|target| (target, true))
+    }
+}
+
+fn normalise_path(path: &Path) -> PathBuf { // lexical so that virtual files and files on disk are identified the same way
+    let mut normalised = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                if !normalised.pop() {
+                    normalised.push("..");
+                }
+            },
+            component => normalised.push(component),
+        }
+    }
+
+    normalised
+}
+
+
+#[cfg(test)]
+mod shader_preprocessor_tests {
+    use std::path::{Path, PathBuf};
+    use crate::RenderError;
+    use crate::shader::{inject_defines, ShaderPreprocessor};
+
+    #[test]
+    fn resolves_relative_include_test() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_virtual_file(Path::new("shaders/common/light.glsl"), "vec3 light() { return vec3(1.0); }");
+
+        let processed = preprocessor.process_source(Path::new("shaders/lit.fsh"), "#version 330 core\n#include \"common/light.glsl\"\nvoid main() {}").unwrap();
+
+        assert_eq!(processed.source, "#version 330 core\n#line 1 1\nvec3 light() { return vec3(1.0); }\n#line 3 0\nvoid main() {}\n");
+        assert_eq!(processed.get_source_file(1), Some(Path::new("shaders/common/light.glsl")));
+    }
+
+    #[test]
+    fn searches_library_paths_test() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_include_path(Path::new("library"));
+        preprocessor.add_virtual_file(Path::new("library/noise.glsl"), "float noise();");
+
+        let processed = preprocessor.process_source(Path::new("shaders/water.fsh"), "#include <noise.glsl>").unwrap();
+
+        assert_eq!(processed.source_files, vec![PathBuf::from("shaders/water.fsh"), PathBuf::from("library/noise.glsl")]);
+    }
+
+    #[test]
+    fn relative_include_wins_over_library_test() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_include_path(Path::new("library"));
+        preprocessor.add_virtual_file(Path::new("library/tonemap.glsl"), "library");
+        preprocessor.add_virtual_file(Path::new("shaders/tonemap.glsl"), "local");
+
+        let processed = preprocessor.process_source(Path::new("shaders/post.fsh"), "#include \"tonemap.glsl\"").unwrap();
+
+        assert!(processed.source.contains("local"));
+        assert!(!processed.source.contains("library"));
+    }
+
+    #[test]
+    fn pragma_once_test() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_virtual_file(Path::new("common.glsl"), "#pragma once\nconst float PI = 3.14159;");
+        preprocessor.add_virtual_file(Path::new("light.glsl"), "#include \"common.glsl\"");
+
+        let processed = preprocessor.process_source(Path::new("main.fsh"), "#include \"common.glsl\"\n#include \"light.glsl\"").unwrap();
+
+        assert_eq!(processed.source.matches("PI").count(), 1);
+    }
+
+    #[test]
+    fn include_cycle_test() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_virtual_file(Path::new("a.glsl"), "#include \"b.glsl\"");
+        preprocessor.add_virtual_file(Path::new("b.glsl"), "\n#include \"a.glsl\"");
+
+        match preprocessor.process_file(Path::new("a.glsl")) {
+            Err(RenderError::ShaderIncludeError { shader_path, line, error }) => {
+                assert_eq!((shader_path.as_str(), line), ("b.glsl", 2));
+                assert!(error.contains("a.glsl -> b.glsl -> a.glsl"));
+            },
+            result => panic!("expected an include cycle error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn missing_include_test() {
+        let preprocessor = ShaderPreprocessor::new();
+
+        assert!(matches!(
+            preprocessor.process_source(Path::new("main.fsh"), "void f();\n#include \"missing.glsl\""),
+            Err(RenderError::ShaderIncludeError { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn inject_defines_after_version_test() {
+        let source = "// header\n#version 330 core\nvoid main() {}";
+
+        let injected = inject_defines(source, &["SKINNED".to_string(), "MAX_LIGHTS 4".to_string()]);
+
+        assert_eq!(injected, "// header\n#version 330 core\n#define SKINNED\n#define MAX_LIGHTS 4\n#line 3 0\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn inject_defines_without_version_test() {
+        assert_eq!(inject_defines("void main() {}", &["SHADOWS".to_string()]), "#define SHADOWS\n#line 1 0\nvoid main() {}\n");
+        assert_eq!(inject_defines("void main() {}", &[]), "void main() {}");
+    }
+
+    #[test]
+    fn nested_version_is_dropped_test() {
+        let mut preprocessor = ShaderPreprocessor::new();
+        preprocessor.add_virtual_file(Path::new("lib/../util.glsl"), "#version 330 core\nfloat util();");
+
+        let processed = preprocessor.process_source(Path::new("main.fsh"), "#version 330 core\n#include \"./util.glsl\"").unwrap();
+
+        assert_eq!(processed.source.matches("#version").count(), 1);
+        assert_eq!(processed.source_files.len(), 2);
+    }
+}
@@ -1,6 +1,31 @@
+use crate::RenderError;
 use crate::shader::set_uniform::SetUniform;
+use crate::shader::{SamplerBinding, ShaderReflection};
+use crate::texture::SamplerTexture;
 
 pub trait ShaderProgram {
     fn bind(&self);
-    fn set_uniform(&mut self, name: String, value: &dyn SetUniform);
+    fn set_uniform(&mut self, name: String, value: &dyn SetUniform); // unknown names and type mismatches are skipped and kept for take_uniform_errors
+
+    fn try_set_uniform(&mut self, name: String, value: &dyn SetUniform) -> Result<(), RenderError> { // like set_uniform but returns the problem instead
+        self.set_uniform(name, value);
+        Ok(())
+    }
+
+    fn set_sampler(&mut self, name: String, texture: &dyn SamplerTexture, unit: u32) { // binds the texture to the unit and points the sampler at it
+        texture.bind_to_unit(unit);
+        self.set_uniform(name, &SamplerBinding { unit, sampler_type: texture.get_sampler_type() });
+    }
+
+    fn get_reflection(&self) -> Option<&ShaderReflection> { // the program's active uniforms, attributes and blocks, if known
+        None
+    }
+
+    fn take_uniform_errors(&mut self) -> Vec<RenderError> { // each bad uniform name is only reported once
+        vec![]
+    }
+
+    fn bind_uniform_block(&mut self, block_name: &str, _binding: u32) -> Result<(), RenderError> { // points the named uniform block at a UniformBuffer's binding point
+        Err(RenderError::UniformError { shader_name: "SHADER_PROGRAM".to_string(), error: format!("Can't bind uniform block {}, this shader program doesn't support uniform blocks", block_name) })
+    }
 }
@@ -0,0 +1,297 @@
+use gl::types::{GLenum, GLint, GLuint};
+use crate::shader::SetUniform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UniformType {
+    Float, Vec2, Vec3, Vec4,
+    Int, IVec2, IVec3, IVec4,
+    UInt, UVec2, UVec3, UVec4,
+    Bool, BVec2, BVec3, BVec4,
+    Mat2, Mat3, Mat4,
+    Sampler1D, Sampler2D, Sampler3D, SamplerCube, Sampler2DArray, Sampler2DShadow, Sampler2DMultisample,
+    ISampler2D, USampler1D, USampler2D, USampler3D,
+    Image2D,
+    Other(GLenum), // anything else GL reports, e.g. double types
+}
+
+impl UniformType {
+    pub fn from_gl(gl_type: GLenum) -> UniformType {
+        match gl_type {
+            gl::FLOAT => UniformType::Float,
+            gl::FLOAT_VEC2 => UniformType::Vec2,
+            gl::FLOAT_VEC3 => UniformType::Vec3,
+            gl::FLOAT_VEC4 => UniformType::Vec4,
+            gl::INT => UniformType::Int,
+            gl::INT_VEC2 => UniformType::IVec2,
+            gl::INT_VEC3 => UniformType::IVec3,
+            gl::INT_VEC4 => UniformType::IVec4,
+            gl::UNSIGNED_INT => UniformType::UInt,
+            gl::UNSIGNED_INT_VEC2 => UniformType::UVec2,
+            gl::UNSIGNED_INT_VEC3 => UniformType::UVec3,
+            gl::UNSIGNED_INT_VEC4 => UniformType::UVec4,
+            gl::BOOL => UniformType::Bool,
+            gl::BOOL_VEC2 => UniformType::BVec2,
+            gl::BOOL_VEC3 => UniformType::BVec3,
+            gl::BOOL_VEC4 => UniformType::BVec4,
+            gl::FLOAT_MAT2 => UniformType::Mat2,
+            gl::FLOAT_MAT3 => UniformType::Mat3,
+            gl::FLOAT_MAT4 => UniformType::Mat4,
+            gl::SAMPLER_1D => UniformType::Sampler1D,
+            gl::SAMPLER_2D => UniformType::Sampler2D,
+            gl::SAMPLER_3D => UniformType::Sampler3D,
+            gl::SAMPLER_CUBE => UniformType::SamplerCube,
+            gl::SAMPLER_2D_ARRAY => UniformType::Sampler2DArray,
+            gl::SAMPLER_2D_SHADOW => UniformType::Sampler2DShadow,
+            gl::SAMPLER_2D_MULTISAMPLE => UniformType::Sampler2DMultisample,
+            gl::INT_SAMPLER_2D => UniformType::ISampler2D,
+            gl::UNSIGNED_INT_SAMPLER_1D => UniformType::USampler1D,
+            gl::UNSIGNED_INT_SAMPLER_2D => UniformType::USampler2D,
+            gl::UNSIGNED_INT_SAMPLER_3D => UniformType::USampler3D,
+            gl::IMAGE_2D => UniformType::Image2D,
+            other => UniformType::Other(other),
+        }
+    }
+
+    pub fn is_opaque(&self) -> bool { // samplers and images, which are set with the unit they read from
+        matches!(self,
+            UniformType::Sampler1D | UniformType::Sampler2D | UniformType::Sampler3D | UniformType::SamplerCube | UniformType::Sampler2DArray
+            | UniformType::Sampler2DShadow | UniformType::Sampler2DMultisample | UniformType::ISampler2D | UniformType::USampler1D | UniformType::USampler2D
+            | UniformType::USampler3D | UniformType::Image2D)
+    }
+
+    pub fn accepts(&self, value_type: UniformType) -> bool { // whether GL allows setting a uniform of this type from a value of value_type
+        match (self, value_type) {
+            (uniform_type, value_type) if *uniform_type == value_type => true,
+            (uniform_type, UniformType::Int) if uniform_type.is_opaque() => true,
+            (UniformType::Sampler2DShadow, UniformType::Sampler2D) => true, // a depth texture read with comparison
+            (UniformType::Bool, UniformType::Int | UniformType::UInt | UniformType::Float) => true, // bools can be set from any scalar
+            (UniformType::BVec2, UniformType::IVec2 | UniformType::UVec2 | UniformType::Vec2) => true,
+            (UniformType::BVec3, UniformType::IVec3 | UniformType::UVec3 | UniformType::Vec3) => true,
+            (UniformType::BVec4, UniformType::IVec4 | UniformType::UVec4 | UniformType::Vec4) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformInfo {
+    pub name: String, // arrays are reported by GL as name[0]
+    pub uniform_type: UniformType,
+    pub array_size: i32,
+    pub location: GLint, // -1 for members of uniform blocks
+    pub block_index: Option<GLuint>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeInfo {
+    pub name: String,
+    pub attribute_type: UniformType,
+    pub array_size: i32,
+    pub location: GLint,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformBlockInfo {
+    pub name: String,
+    pub index: GLuint,
+    pub binding: GLint,
+    pub data_size: GLint, // in bytes, as laid out by the driver
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShaderReflection {
+    pub uniforms: Vec<UniformInfo>,
+    pub attributes: Vec<AttributeInfo>,
+    pub uniform_blocks: Vec<UniformBlockInfo>,
+}
+
+impl ShaderReflection {
+    pub fn from_program(program_id: GLuint) -> ShaderReflection { // the program must be linked
+        let mut reflection = ShaderReflection::default();
+
+        unsafe {
+            let mut count = 0;
+            gl::GetProgramiv(program_id, gl::ACTIVE_UNIFORMS, &mut count);
+            for index in 0..count as GLuint {
+                let (name, array_size, gl_type) = Self::read_active(program_id, index, gl::ACTIVE_UNIFORM_MAX_LENGTH, gl::GetActiveUniform);
+                let location = Self::get_location(program_id, &name, gl::GetUniformLocation);
+
+                let mut block_index = -1;
+                gl::GetActiveUniformsiv(program_id, 1, &index, gl::UNIFORM_BLOCK_INDEX, &mut block_index);
+
+                reflection.uniforms.push(UniformInfo {
+                    name,
+                    uniform_type: UniformType::from_gl(gl_type),
+                    array_size,
+                    location,
+                    block_index: if block_index < 0 { None } else { Some(block_index as GLuint) },
+                });
+            }
+
+            gl::GetProgramiv(program_id, gl::ACTIVE_ATTRIBUTES, &mut count);
+            for index in 0..count as GLuint {
+                let (name, array_size, gl_type) = Self::read_active(program_id, index, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, gl::GetActiveAttrib);
+                let location = Self::get_location(program_id, &name, gl::GetAttribLocation);
+
+                reflection.attributes.push(AttributeInfo { name, attribute_type: UniformType::from_gl(gl_type), array_size, location });
+            }
+
+            gl::GetProgramiv(program_id, gl::ACTIVE_UNIFORM_BLOCKS, &mut count);
+            let mut max_length = 0;
+            gl::GetProgramiv(program_id, gl::ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH, &mut max_length);
+            for index in 0..count as GLuint {
+                let mut name: Vec<u8> = vec![0; max_length.max(1) as usize];
+                let mut length = 0;
+                gl::GetActiveUniformBlockName(program_id, index, max_length, &mut length, name.as_mut_ptr().cast());
+                name.truncate(length as usize);
+
+                let (mut binding, mut data_size) = (0, 0);
+                gl::GetActiveUniformBlockiv(program_id, index, gl::UNIFORM_BLOCK_BINDING, &mut binding);
+                gl::GetActiveUniformBlockiv(program_id, index, gl::UNIFORM_BLOCK_DATA_SIZE, &mut data_size);
+
+                reflection.uniform_blocks.push(UniformBlockInfo { name: String::from_utf8_lossy(&name).to_string(), index, binding, data_size });
+            }
+        }
+
+        reflection
+    }
+
+    unsafe fn read_active(
+        program_id: GLuint,
+        index: GLuint,
+        max_length_query: GLenum,
+        get_active: unsafe fn(GLuint, GLuint, gl::types::GLsizei, *mut gl::types::GLsizei, *mut GLint, *mut GLenum, *mut gl::types::GLchar),
+    ) -> (String, i32, GLenum) {
+        let mut max_length = 0;
+        gl::GetProgramiv(program_id, max_length_query, &mut max_length);
+
+        let mut name: Vec<u8> = vec![0; max_length.max(1) as usize];
+        let (mut length, mut array_size, mut gl_type) = (0, 0, 0);
+        get_active(program_id, index, max_length, &mut length, &mut array_size, &mut gl_type, name.as_mut_ptr().cast());
+        name.truncate(length as usize);
+
+        (String::from_utf8_lossy(&name).to_string(), array_size, gl_type)
+    }
+
+    unsafe fn get_location(program_id: GLuint, name: &str, get_location: unsafe fn(GLuint, *const gl::types::GLchar) -> GLint) -> GLint {
+        let c_name = std::ffi::CString::new(name).unwrap(); // GL names never contain a nul
+        get_location(program_id, c_name.as_ptr())
+    }
+
+    pub fn get_uniform(&self, name: &str) -> Option<&UniformInfo> { // finds arrays by their name with or without [0]
+        self.uniforms.iter().find(|uniform| uniform.name == name || uniform.name.strip_suffix("[0]") == Some(name))
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&AttributeInfo> {
+        self.attributes.iter().find(|attribute| attribute.name == name)
+    }
+
+    pub fn get_uniform_block(&self, name: &str) -> Option<&UniformBlockInfo> {
+        self.uniform_blocks.iter().find(|block| block.name == name)
+    }
+
+    // Checks a uniform can be set by name with a value of value_type, None skips the type check.
+    // Elements of arrays, e.g. lights[2], are checked against the array.
+    pub fn check_uniform(&self, name: &str, value_type: Option<UniformType>) -> Result<&UniformInfo, String> {
+        let uniform = match self.get_uniform(name) {
+            Some(uniform) => uniform,
+            None => match split_array_index(name) {
+                Some((base, index)) => match self.get_uniform(base) {
+                    Some(uniform) if index < uniform.array_size.max(0) as usize => uniform,
+                    Some(uniform) => return Err(format!("{} is out of bounds for an array of {}", name, uniform.array_size)),
+                    None => return Err(format!("{} isn't an active uniform", name)),
+                },
+                None => return Err(format!("{} isn't an active uniform", name)), // misspelt, or optimised out by the driver
+            },
+        };
+
+        if uniform.block_index.is_some() {
+            return Err(format!("{} is in a uniform block, set it through the block's buffer", name));
+        }
+
+        match value_type {
+            Some(value_type) if !uniform.uniform_type.accepts(value_type) => Err(format!("{} is a {:?} but was given a {:?}", name, uniform.uniform_type, value_type)),
+            _ => Ok(uniform),
+        }
+    }
+
+    pub fn check_uniform_value(&self, name: &str, value: &dyn SetUniform) -> Result<&UniformInfo, String> { // check_uniform, plus arrays fitting in what's left of the GLSL array
+        let uniform = self.check_uniform(name, value.uniform_type())?;
+
+        if let Some(length) = value.array_len() {
+            let start = match self.get_uniform(name) {
+                Some(_) => 0,
+                None => split_array_index(name).map(|(_, index)| index).unwrap_or(0),
+            };
+
+            if start + length > uniform.array_size.max(1) as usize {
+                return Err(format!("{} values starting at {} overflow an array of {}", length, name, uniform.array_size));
+            }
+        }
+
+        Ok(uniform)
+    }
+}
+
+fn split_array_index(name: &str) -> Option<(&str, usize)> { // "lights[2]" -> ("lights", 2)
+    let (base, index) = name.strip_suffix(']')?.rsplit_once('[')?;
+    Some((base, index.parse().ok()?))
+}
+
+
+#[cfg(test)]
+mod shader_reflection_tests {
+    use crate::shader::{ShaderReflection, UniformInfo, UniformType};
+    use crate::types::{vec3, Mat4};
+
+    fn uniform(name: &str, uniform_type: UniformType, array_size: i32, block_index: Option<u32>) -> UniformInfo {
+        UniformInfo { name: name.to_string(), uniform_type, array_size, location: 0, block_index }
+    }
+
+    fn reflection() -> ShaderReflection {
+        ShaderReflection {
+            uniforms: vec![
+                uniform("model", UniformType::Mat4, 1, None),
+                uniform("lights[0]", UniformType::Vec3, 4, None),
+                uniform("albedo", UniformType::Sampler2D, 1, None),
+                uniform("use_fog", UniformType::Bool, 1, None),
+                uniform("camera.position", UniformType::Vec3, 1, Some(0)),
+            ],
+            ..ShaderReflection::default()
+        }
+    }
+
+    #[test]
+    fn check_uniform_types_test() {
+        let reflection = reflection();
+
+        assert!(reflection.check_uniform("model", Some(UniformType::Mat4)).is_ok());
+        assert!(reflection.check_uniform("model", Some(UniformType::Vec4)).is_err());
+        assert!(reflection.check_uniform("albedo", Some(UniformType::Int)).is_ok());
+        assert!(reflection.check_uniform("use_fog", Some(UniformType::Float)).is_ok());
+        assert!(reflection.check_uniform("model", None).is_ok());
+    }
+
+    #[test]
+    fn check_uniform_arrays_test() {
+        let reflection = reflection();
+
+        assert_eq!(reflection.check_uniform("lights", Some(UniformType::Vec3)).unwrap().name, "lights[0]");
+        assert!(reflection.check_uniform("lights[3]", Some(UniformType::Vec3)).is_ok());
+        assert!(reflection.check_uniform("lights[4]", Some(UniformType::Vec3)).is_err());
+
+        let three_lights = [vec3(0.0, 0.0, 0.0); 3];
+        assert!(reflection.check_uniform_value("lights", &three_lights).is_ok());
+        assert!(reflection.check_uniform_value("lights[1]", &three_lights).is_ok());
+        assert!(reflection.check_uniform_value("lights[2]", &three_lights).unwrap_err().contains("overflow"));
+        assert!(reflection.check_uniform_value("model", &vec![Mat4::identity(); 2]).is_err());
+    }
+
+    #[test]
+    fn check_uniform_missing_and_block_members_test() {
+        let reflection = reflection();
+
+        assert!(reflection.check_uniform("modle", None).unwrap_err().contains("isn't an active uniform"));
+        assert!(reflection.check_uniform("camera.position", None).unwrap_err().contains("uniform block"));
+    }
+}
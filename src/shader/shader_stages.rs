@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use gl::types::GLenum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShaderStage { // declared in pipeline order
+    Vertex,
+    TessControl,
+    TessEvaluation,
+    Geometry,
+    Fragment,
+    Compute,
+}
+
+impl ShaderStage {
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            ShaderStage::Vertex => gl::VERTEX_SHADER,
+            ShaderStage::TessControl => gl::TESS_CONTROL_SHADER,
+            ShaderStage::TessEvaluation => gl::TESS_EVALUATION_SHADER,
+            ShaderStage::Geometry => gl::GEOMETRY_SHADER,
+            ShaderStage::Fragment => gl::FRAGMENT_SHADER,
+            ShaderStage::Compute => gl::COMPUTE_SHADER,
+        }
+    }
+
+    pub fn from_gl(stage: GLenum) -> Option<ShaderStage> {
+        match stage {
+            gl::VERTEX_SHADER => Some(ShaderStage::Vertex),
+            gl::TESS_CONTROL_SHADER => Some(ShaderStage::TessControl),
+            gl::TESS_EVALUATION_SHADER => Some(ShaderStage::TessEvaluation),
+            gl::GEOMETRY_SHADER => Some(ShaderStage::Geometry),
+            gl::FRAGMENT_SHADER => Some(ShaderStage::Fragment),
+            gl::COMPUTE_SHADER => Some(ShaderStage::Compute),
+            _ => None,
+        }
+    }
+
+    pub fn get_extension(&self) -> &'static str { // the file extension used when loading {filepath}.ext
+        match self {
+            ShaderStage::Vertex => "vsh",
+            ShaderStage::TessControl => "tcs",
+            ShaderStage::TessEvaluation => "tes",
+            ShaderStage::Geometry => "gsh",
+            ShaderStage::Fragment => "fsh",
+            ShaderStage::Compute => "csh",
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ShaderStage::Vertex => "VERTEX",
+            ShaderStage::TessControl => "TESS_CONTROL",
+            ShaderStage::TessEvaluation => "TESS_EVALUATION",
+            ShaderStage::Geometry => "GEOMETRY",
+            ShaderStage::Fragment => "FRAGMENT",
+            ShaderStage::Compute => "COMPUTE",
+        }
+    }
+}
+
+impl fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get_name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StageSource {
+    File(PathBuf),
+    Source { path: PathBuf, source: String }, // the path only names it in errors and resolves relative #includes
+}
+
+// The stages that make up a program, each from its own file or source string, e.g.
+//
+// ShaderStages::new()
+//     .with_file(ShaderStage::Vertex, "shaders/terrain.vsh")
+//     .with_file(ShaderStage::TessControl, "shaders/terrain.tcs")
+//     .with_file(ShaderStage::TessEvaluation, "shaders/terrain.tes")
+//     .with_source(ShaderStage::Fragment, include_str!("flat.fsh"))
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShaderStages {
+    stages: BTreeMap<ShaderStage, StageSource>,
+}
+
+impl ShaderStages {
+    pub fn new() -> ShaderStages {
+        ShaderStages { stages: BTreeMap::new() }
+    }
+
+    pub fn from_base_path(filepath: &str, stages: &[ShaderStage]) -> ShaderStages { // {filepath}.vsh, {filepath}.fsh and so on
+        stages.iter().fold(ShaderStages::new(), |shader_stages, stage| {
+            shader_stages.with_file(*stage, format!("{}.{}", filepath, stage.get_extension()))
+        })
+    }
+
+    pub fn with_file(mut self, stage: ShaderStage, path: impl Into<PathBuf>) -> ShaderStages {
+        self.stages.insert(stage, StageSource::File(path.into()));
+        self
+    }
+
+    pub fn with_source(self, stage: ShaderStage, source: &str) -> ShaderStages {
+        let path = format!("{}.{}", stage.get_name().to_lowercase(), stage.get_extension());
+        self.with_named_source(stage, path, source)
+    }
+
+    pub fn with_named_source(mut self, stage: ShaderStage, path: impl Into<PathBuf>, source: &str) -> ShaderStages {
+        self.stages.insert(stage, StageSource::Source { path: path.into(), source: source.to_string() });
+        self
+    }
+
+    pub fn get_stage(&self, stage: ShaderStage) -> Option<&StageSource> {
+        self.stages.get(&stage)
+    }
+
+    pub fn get_stages(&self) -> impl Iterator<Item = (ShaderStage, &StageSource)> { // in pipeline order
+        self.stages.iter().map(|(stage, source)| (*stage, source))
+    }
+
+    pub fn get_source_paths(&self) -> Vec<PathBuf> { // the files to watch for hot reloading, source strings don't change
+        self.stages.values()
+            .filter_map(|source| match source {
+                StageSource::File(path) => Some(path.clone()),
+                StageSource::Source { .. } => None,
+            })
+            .collect()
+    }
+
+    pub fn validate(&self) -> Result<(), String> { // catches combinations that could never link before anything is compiled
+        if self.stages.is_empty() {
+            return Err("Program has no shader stages!".to_string());
+        }
+
+        if self.stages.contains_key(&ShaderStage::Compute) {
+            return match self.stages.len() {
+                1 => Ok(()),
+                _ => Err("A compute stage can't be linked with other stages!".to_string()),
+            };
+        }
+
+        if !self.stages.contains_key(&ShaderStage::Vertex) {
+            return Err("Program has no vertex stage!".to_string());
+        }
+
+        if self.stages.contains_key(&ShaderStage::TessControl) && !self.stages.contains_key(&ShaderStage::TessEvaluation) {
+            return Err("A tessellation control stage needs a tessellation evaluation stage!".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod shader_stages_tests {
+    use std::path::PathBuf;
+    use crate::shader::{ShaderStage, ShaderStages, StageSource};
+
+    #[test]
+    fn from_base_path_test() {
+        let stages = ShaderStages::from_base_path("shaders/grass", &[ShaderStage::Fragment, ShaderStage::Geometry, ShaderStage::Vertex]);
+
+        let stage_list: Vec<(ShaderStage, &StageSource)> = stages.get_stages().collect();
+        assert_eq!(stage_list, vec![
+            (ShaderStage::Vertex, &StageSource::File(PathBuf::from("shaders/grass.vsh"))),
+            (ShaderStage::Geometry, &StageSource::File(PathBuf::from("shaders/grass.gsh"))),
+            (ShaderStage::Fragment, &StageSource::File(PathBuf::from("shaders/grass.fsh"))),
+        ]);
+        assert_eq!(ShaderStage::Geometry.to_gl(), gl::GEOMETRY_SHADER);
+    }
+
+    #[test]
+    fn source_strings_are_not_watched_test() {
+        let stages = ShaderStages::new()
+            .with_file(ShaderStage::Vertex, "a.vsh")
+            .with_source(ShaderStage::Fragment, "void main() {}");
+
+        assert_eq!(stages.get_source_paths(), vec![PathBuf::from("a.vsh")]);
+        assert!(matches!(stages.get_stage(ShaderStage::Fragment), Some(StageSource::Source { .. })));
+    }
+
+    #[test]
+    fn validate_test() {
+        assert!(ShaderStages::new().validate().is_err());
+        assert!(ShaderStages::new().with_source(ShaderStage::Fragment, "").validate().is_err());
+        assert!(ShaderStages::new().with_source(ShaderStage::Compute, "").with_source(ShaderStage::Vertex, "").validate().is_err());
+        assert!(ShaderStages::from_base_path("a", &[ShaderStage::Vertex, ShaderStage::TessControl, ShaderStage::Fragment]).validate().is_err());
+
+        assert!(ShaderStages::new().with_source(ShaderStage::Compute, "").validate().is_ok());
+        assert!(ShaderStages::from_base_path("a", &[ShaderStage::Vertex, ShaderStage::TessControl, ShaderStage::TessEvaluation, ShaderStage::Fragment]).validate().is_ok());
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt::{Display, Formatter};
+use gl::types::GLint;
+use crate::shader::{SamplerBinding, SetUniform, UniformArrayElement, UniformType};
+use crate::types::{IVec2, IVec3, IVec4, Mat3, Mat4, UVec2, Vec2, Vec3, Vec4};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UniformValue { // a uniform write as data, so it can be recorded, compared and stored, e.g. in a material
+    Float(f32),
+    Int(i32),
+    UInt(u32),
+    Bool(bool),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    IVec2(IVec2),
+    IVec3(IVec3),
+    IVec4(IVec4),
+    UVec2(UVec2),
+    Mat3(Mat3),
+    Mat4(Mat4),
+    Sampler(SamplerBinding),
+    Array(Vec<UniformValue>), // all the same variant
+    Other(String), // a custom SetUniform that has no typed form
+}
+
+impl Display for UniformValue {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            UniformValue::Float(value) => write!(f, "{}", value),
+            UniformValue::Int(value) => write!(f, "{}", value),
+            UniformValue::UInt(value) => write!(f, "{}", value),
+            UniformValue::Bool(value) => write!(f, "{}", value),
+            UniformValue::Vec2(value) => write!(f, "{}", value),
+            UniformValue::Vec3(value) => write!(f, "{}", value),
+            UniformValue::Vec4(value) => write!(f, "{}", value),
+            UniformValue::IVec2(value) => write!(f, "{}", value),
+            UniformValue::IVec3(value) => write!(f, "{}", value),
+            UniformValue::IVec4(value) => write!(f, "{}", value),
+            UniformValue::UVec2(value) => write!(f, "{}", value),
+            UniformValue::Mat3(value) => write!(f, "{}", value),
+            UniformValue::Mat4(value) => write!(f, "{}", value),
+            UniformValue::Sampler(sampler) => write!(f, "{:?}({})", sampler.sampler_type, sampler.unit),
+            UniformValue::Array(values) => write!(f, "[{}]", values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(", ")),
+            UniformValue::Other(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl UniformValue {
+    unsafe fn set_uniform_array(values: &[UniformValue], location: GLint) { // one glUniform*v call for the whole array, elements that don't match the first one's type leave it unset
+        macro_rules! set_typed_array {
+            ($variant:ident, $element:ty) => {
+                let typed: Option<Vec<$element>> = values.iter()
+                    .map(|value| match value { UniformValue::$variant(value) => Some(value.clone()), _ => None })
+                    .collect();
+                if let Some(typed) = typed {
+                    <$element>::set_uniform_array(&typed, location);
+                }
+            };
+        }
+
+        match values.first() {
+            Some(UniformValue::Float(_)) => { set_typed_array!(Float, f32); },
+            Some(UniformValue::Int(_)) => { set_typed_array!(Int, i32); },
+            Some(UniformValue::UInt(_)) => { set_typed_array!(UInt, u32); },
+            Some(UniformValue::Bool(_)) => { set_typed_array!(Bool, bool); },
+            Some(UniformValue::Vec2(_)) => { set_typed_array!(Vec2, Vec2); },
+            Some(UniformValue::Vec3(_)) => { set_typed_array!(Vec3, Vec3); },
+            Some(UniformValue::Vec4(_)) => { set_typed_array!(Vec4, Vec4); },
+            Some(UniformValue::IVec2(_)) => { set_typed_array!(IVec2, IVec2); },
+            Some(UniformValue::IVec3(_)) => { set_typed_array!(IVec3, IVec3); },
+            Some(UniformValue::IVec4(_)) => { set_typed_array!(IVec4, IVec4); },
+            Some(UniformValue::UVec2(_)) => { set_typed_array!(UVec2, UVec2); },
+            Some(UniformValue::Mat3(_)) => { set_typed_array!(Mat3, Mat3); },
+            Some(UniformValue::Mat4(_)) => { set_typed_array!(Mat4, Mat4); },
+            Some(UniformValue::Sampler(_)) => {
+                let units: Option<Vec<i32>> = values.iter()
+                    .map(|value| match value { UniformValue::Sampler(sampler) => Some(sampler.unit as i32), _ => None })
+                    .collect();
+                if let Some(units) = units {
+                    i32::set_uniform_array(&units, location);
+                }
+            },
+            Some(UniformValue::Array(_) | UniformValue::Other(_)) | None => {},
+        }
+    }
+
+    fn as_set_uniform(&self) -> Option<&dyn SetUniform> {
+        match self {
+            UniformValue::Float(value) => Some(value),
+            UniformValue::Int(value) => Some(value),
+            UniformValue::UInt(value) => Some(value),
+            UniformValue::Bool(value) => Some(value),
+            UniformValue::Vec2(value) => Some(value),
+            UniformValue::Vec3(value) => Some(value),
+            UniformValue::Vec4(value) => Some(value),
+            UniformValue::IVec2(value) => Some(value),
+            UniformValue::IVec3(value) => Some(value),
+            UniformValue::IVec4(value) => Some(value),
+            UniformValue::UVec2(value) => Some(value),
+            UniformValue::Mat3(value) => Some(value),
+            UniformValue::Mat4(value) => Some(value),
+            UniformValue::Sampler(sampler) => Some(sampler),
+            UniformValue::Array(_) | UniformValue::Other(_) => None,
+        }
+    }
+}
+
+impl SetUniform for UniformValue { // so a stored value can be written back, e.g. a material's defaults
+    unsafe fn set_uniform(&self, location: GLint) {
+        match self {
+            UniformValue::Array(values) => Self::set_uniform_array(values, location),
+            value => if let Some(value) = value.as_set_uniform() {
+                value.set_uniform(location);
+            },
+        }
+    }
+
+    fn to_uniform_value(&self) -> UniformValue {
+        self.clone()
+    }
+
+    fn uniform_type(&self) -> Option<UniformType> {
+        match self {
+            UniformValue::Array(values) => values.first().and_then(|value| value.uniform_type()),
+            value => value.as_set_uniform().and_then(|value| value.uniform_type()),
+        }
+    }
+
+    fn array_len(&self) -> Option<usize> {
+        match self {
+            UniformValue::Array(values) => Some(values.len()),
+            _ => None,
+        }
+    }
+}
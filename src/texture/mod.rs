@@ -7,8 +7,10 @@ mod gl_texture_2d_u8;
 mod gl_texture_3d;
 mod gl_texture_3d_int;
 mod gl_texture_3d_u8;
+mod sampler_texture;
 
 pub use texture_manager::TextureManager;
+pub use sampler_texture::SamplerTexture;
 pub use gl_texture_1d::Texture1D;
 pub use gl_texture_1d_int::Texture1DInt;
 pub use gl_texture_2d::Texture2D;
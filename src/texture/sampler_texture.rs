@@ -0,0 +1,43 @@
+use crate::shader::UniformType;
+use crate::texture::{MockTexture1D, MockTexture1DInt, MockTexture2D, MockTexture2DInt, MockTexture2Du8, MockTexture3D, MockTexture3DInt, MockTexture3Du8};
+use crate::texture::{Texture1D, Texture1DInt, Texture2D, Texture2DInt, Texture2Du8, Texture3D, Texture3DInt, Texture3Du8};
+
+pub trait SamplerTexture { // a texture a shader reads through a sampler uniform, see ShaderProgram::set_sampler
+    fn bind_to_unit(&self, unit: u32);
+    fn get_sampler_type(&self) -> UniformType; // the GLSL sampler it has to be read through, the integer textures need a usampler
+}
+
+macro_rules! impl_sampler_texture {
+    ($($texture:ty => $sampler_type:expr),* $(,)?) => {
+        $(
+            impl SamplerTexture for $texture {
+                fn bind_to_unit(&self, unit: u32) {
+                    <$texture>::bind_to_unit(self, unit);
+                }
+
+                fn get_sampler_type(&self) -> UniformType {
+                    $sampler_type
+                }
+            }
+        )*
+    };
+}
+
+impl_sampler_texture!(
+    Texture1D => UniformType::Sampler1D,
+    Texture1DInt => UniformType::USampler1D,
+    Texture2D => UniformType::Sampler2D,
+    Texture2DInt => UniformType::USampler2D,
+    Texture2Du8 => UniformType::USampler2D,
+    Texture3D => UniformType::Sampler3D,
+    Texture3DInt => UniformType::USampler3D,
+    Texture3Du8 => UniformType::USampler3D,
+    MockTexture1D => UniformType::Sampler1D,
+    MockTexture1DInt => UniformType::USampler1D,
+    MockTexture2D => UniformType::Sampler2D,
+    MockTexture2DInt => UniformType::USampler2D,
+    MockTexture2Du8 => UniformType::USampler2D,
+    MockTexture3D => UniformType::Sampler3D,
+    MockTexture3DInt => UniformType::USampler3D,
+    MockTexture3Du8 => UniformType::USampler3D,
+);
@@ -0,0 +1,4 @@
+pub trait ClockSource { // all times are in seconds
+    fn now(&self) -> f64;
+    fn sleep(&self, seconds: f64);
+}
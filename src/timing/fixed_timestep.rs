@@ -0,0 +1,82 @@
+pub struct FixedTimestep {
+    step: f64,
+    accumulator: f64,
+    max_steps_per_frame: u32, // stops a slow frame from causing ever more updates (the "spiral of death")
+}
+
+impl FixedTimestep {
+    pub fn new(updates_per_second: f64) -> FixedTimestep {
+        assert!(updates_per_second > 0.0 && updates_per_second.is_finite(), "updates_per_second must be positive and finite, got {}", updates_per_second);
+
+        FixedTimestep {
+            step: 1.0 / updates_per_second,
+            accumulator: 0.0,
+            max_steps_per_frame: 8,
+        }
+    }
+
+    pub fn set_max_steps_per_frame(&mut self, max_steps_per_frame: u32) {
+        self.max_steps_per_frame = max_steps_per_frame;
+    }
+
+    pub fn advance(&mut self, delta_time: f64) -> u32 { // returns how many updates to run this frame
+        self.accumulator += delta_time.max(0.0);
+
+        let steps = (self.accumulator / self.step).floor() as u32;
+        let run_steps = steps.min(self.max_steps_per_frame);
+
+        if run_steps < steps {
+            self.accumulator = 0.0; // drop the time we can't catch up on
+        } else {
+            self.accumulator -= run_steps as f64 * self.step;
+        }
+
+        run_steps
+    }
+
+    pub fn get_alpha(&self) -> f64 { // how far between the last two updates to interpolate when rendering
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+
+    pub fn get_step(&self) -> f64 {
+        self.step
+    }
+}
+
+
+#[cfg(test)]
+mod fixed_timestep_tests {
+    use crate::timing::FixedTimestep;
+
+    #[test]
+    fn advance_runs_whole_steps_test() {
+        let mut timestep = FixedTimestep::new(8.0);
+
+        assert_eq!(timestep.advance(0.3125), 2);
+        assert!((timestep.get_alpha() - 0.5).abs() < 1e-9);
+
+        assert_eq!(timestep.advance(0.0625), 1);
+        assert!(timestep.get_alpha().abs() < 1e-9);
+    }
+
+    #[test]
+    fn advance_clamps_steps_test() {
+        let mut timestep = FixedTimestep::new(100.0);
+        timestep.set_max_steps_per_frame(4);
+
+        assert_eq!(timestep.advance(1.0), 4);
+        assert_eq!(timestep.get_alpha(), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "updates_per_second must be positive and finite")]
+    fn zero_rate_panics_test() {
+        FixedTimestep::new(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "updates_per_second must be positive and finite")]
+    fn nan_rate_panics_test() {
+        FixedTimestep::new(f64::NAN);
+    }
+}
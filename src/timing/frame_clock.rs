@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+use crate::timing::ClockSource;
+
+const FRAME_HISTORY_LENGTH: usize = 240;
+const FPS_SMOOTHING: f64 = 0.1; // weight of the newest frame in the moving average
+const HISTOGRAM_BUCKET_WIDTH_MS: f64 = 1.0;
+const HISTOGRAM_BUCKET_COUNT: usize = 50; // the last bucket also collects anything slower
+
+pub struct FrameClock {
+    clock: Box<dyn ClockSource>,
+    frame_start: f64,
+    start_time: f64,
+    delta_time: f64,
+    smoothed_delta_time: f64,
+    frame_count: u64,
+    frame_rate_cap: Option<f64>,
+    frame_times: VecDeque<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameTimeHistogram {
+    pub bucket_width_ms: f64,
+    pub buckets: Vec<u32>,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub average_ms: f64,
+    frame_times_ms: Vec<f64>, // sorted, for percentiles
+}
+
+impl FrameClock {
+    pub fn new(clock: Box<dyn ClockSource>) -> FrameClock {
+        let now = clock.now();
+
+        FrameClock {
+            clock,
+            frame_start: now,
+            start_time: now,
+            delta_time: 0.0,
+            smoothed_delta_time: 0.0,
+            frame_count: 0,
+            frame_rate_cap: None,
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY_LENGTH),
+        }
+    }
+
+    pub fn tick(&mut self, apply_cap: bool) -> f64 { // call once per frame, returns the delta time in seconds
+        if let (true, Some(cap)) = (apply_cap, self.frame_rate_cap) {
+            let remaining = (1.0 / cap) - (self.clock.now() - self.frame_start);
+            self.clock.sleep(remaining.max(0.0));
+        }
+
+        let now = self.clock.now();
+        self.delta_time = (now - self.frame_start).max(0.0);
+        self.frame_start = now;
+        self.frame_count += 1;
+
+        self.smoothed_delta_time = if self.frame_count == 1 {
+            self.delta_time
+        } else {
+            self.smoothed_delta_time * (1.0 - FPS_SMOOTHING) + self.delta_time * FPS_SMOOTHING
+        };
+
+        if self.frame_times.len() == FRAME_HISTORY_LENGTH {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(self.delta_time);
+
+        self.delta_time
+    }
+
+    pub fn set_clock_source(&mut self, clock: Box<dyn ClockSource>) {
+        *self = FrameClock { frame_rate_cap: self.frame_rate_cap, ..FrameClock::new(clock) };
+    }
+
+    pub fn set_frame_rate_cap(&mut self, frame_rate_cap: Option<f64>) {
+        self.frame_rate_cap = frame_rate_cap.filter(|cap| *cap > 0.0);
+    }
+
+    pub fn get_frame_rate_cap(&self) -> Option<f64> {
+        self.frame_rate_cap
+    }
+
+    pub fn get_delta_time(&self) -> f64 {
+        self.delta_time
+    }
+
+    pub fn get_fps(&self) -> f64 {
+        if self.smoothed_delta_time > 0.0 { 1.0 / self.smoothed_delta_time } else { 0.0 }
+    }
+
+    pub fn get_frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn get_elapsed_time(&self) -> f64 {
+        self.clock.now() - self.start_time
+    }
+
+    pub fn get_frame_time_histogram(&self) -> FrameTimeHistogram {
+        let mut frame_times_ms: Vec<f64> = self.frame_times.iter().map(|time| time * 1000.0).collect();
+        frame_times_ms.sort_by(|a, b| a.total_cmp(b));
+
+        let mut buckets = vec![0; HISTOGRAM_BUCKET_COUNT];
+        for time in &frame_times_ms {
+            let bucket = ((time / HISTOGRAM_BUCKET_WIDTH_MS) as usize).min(HISTOGRAM_BUCKET_COUNT - 1);
+            buckets[bucket] += 1;
+        }
+
+        FrameTimeHistogram {
+            bucket_width_ms: HISTOGRAM_BUCKET_WIDTH_MS,
+            buckets,
+            min_ms: frame_times_ms.first().copied().unwrap_or(0.0),
+            max_ms: frame_times_ms.last().copied().unwrap_or(0.0),
+            average_ms: if frame_times_ms.is_empty() { 0.0 } else { frame_times_ms.iter().sum::<f64>() / frame_times_ms.len() as f64 },
+            frame_times_ms,
+        }
+    }
+}
+
+impl FrameTimeHistogram {
+    pub fn percentile(&self, percentile: f64) -> f64 { // e.g. 99.0 gives the frame time 99% of frames beat
+        if self.frame_times_ms.is_empty() {
+            return 0.0;
+        }
+
+        let index = ((percentile.clamp(0.0, 100.0) / 100.0) * (self.frame_times_ms.len() - 1) as f64).round() as usize;
+        self.frame_times_ms[index]
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_times_ms.len()
+    }
+}
+
+
+#[cfg(test)]
+mod frame_clock_tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::timing::{FrameClock, NullableClock};
+
+    type ClockHandles = (FrameClock, Rc<RefCell<f64>>, Rc<RefCell<Vec<f64>>>);
+
+    fn new_clock() -> ClockHandles {
+        let time = Rc::new(RefCell::new(0.0));
+        let sleeps = Rc::new(RefCell::new(vec![]));
+        let frame_clock = FrameClock::new(Box::new(NullableClock::new(time.clone(), sleeps.clone())));
+
+        (frame_clock, time, sleeps)
+    }
+
+    #[test]
+    fn delta_time_test() {
+        let (mut frame_clock, time, _) = new_clock();
+
+        time.replace(0.016);
+        let delta = frame_clock.tick(false);
+
+        assert_eq!(delta, 0.016);
+        assert_eq!(frame_clock.get_delta_time(), 0.016);
+        assert_eq!(frame_clock.get_frame_count(), 1);
+    }
+
+    #[test]
+    fn fps_test() {
+        let (mut frame_clock, time, _) = new_clock();
+
+        for frame in 1..=10 {
+            time.replace(frame as f64 * 0.02);
+            frame_clock.tick(false);
+        }
+
+        assert!((frame_clock.get_fps() - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn frame_rate_cap_sleeps_for_remaining_time_test() {
+        let (mut frame_clock, time, sleeps) = new_clock();
+        frame_clock.set_frame_rate_cap(Some(50.0));
+
+        time.replace(0.005);
+        let delta = frame_clock.tick(true);
+
+        assert!((delta - 0.02).abs() < 1e-9);
+        assert_eq!(sleeps.borrow().len(), 1);
+        assert!((sleeps.borrow()[0] - 0.015).abs() < 1e-9);
+    }
+
+    #[test]
+    fn frame_rate_cap_ignored_when_not_applied_test() {
+        let (mut frame_clock, time, sleeps) = new_clock();
+        frame_clock.set_frame_rate_cap(Some(50.0));
+
+        time.replace(0.005);
+        frame_clock.tick(false);
+
+        assert!(sleeps.borrow().is_empty());
+    }
+
+    #[test]
+    fn histogram_test() {
+        let (mut frame_clock, time, _) = new_clock();
+
+        for delta in [0.0105, 0.0105, 0.0105, 0.0305] {
+            let now = *time.borrow() + delta;
+            time.replace(now);
+            frame_clock.tick(false);
+        }
+
+        let histogram = frame_clock.get_frame_time_histogram();
+
+        assert_eq!(histogram.frame_count(), 4);
+        assert_eq!(histogram.buckets[10], 3);
+        assert_eq!(histogram.buckets[30], 1);
+        assert!((histogram.max_ms - 30.5).abs() < 1e-6);
+        assert!((histogram.min_ms - 10.5).abs() < 1e-6);
+        assert!((histogram.percentile(100.0) - 30.5).abs() < 1e-6);
+        assert!((histogram.percentile(50.0) - 10.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,11 @@
+mod clock_source;
+mod system_clock;
+mod nullable_clock;
+mod frame_clock;
+mod fixed_timestep;
+
+pub use clock_source::ClockSource;
+pub use system_clock::SystemClock;
+pub use nullable_clock::NullableClock;
+pub use frame_clock::{FrameClock, FrameTimeHistogram};
+pub use fixed_timestep::FixedTimestep;
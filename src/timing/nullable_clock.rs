@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::timing::ClockSource;
+
+pub struct NullableClock {
+    time: Rc<RefCell<f64>>,
+    sleeps: Rc<RefCell<Vec<f64>>>,
+}
+
+impl ClockSource for NullableClock {
+    fn now(&self) -> f64 {
+        *self.time.borrow()
+    }
+
+    //Sleeping just moves the clock forward so capped frame rates stay deterministic
+    fn sleep(&self, seconds: f64) {
+        self.sleeps.borrow_mut().push(seconds);
+        *self.time.borrow_mut() += seconds;
+    }
+}
+
+impl NullableClock {
+    pub fn new(time: Rc<RefCell<f64>>, sleeps: Rc<RefCell<Vec<f64>>>) -> Self {
+        Self {
+            time,
+            sleeps,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod nullable_clock_tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::timing::{ClockSource, NullableClock};
+
+    #[test]
+    fn now_test() {
+        let time = Rc::new(RefCell::new(1.5));
+        let clock = NullableClock::new(time.clone(), Rc::new(RefCell::new(vec![])));
+
+        assert_eq!(clock.now(), 1.5);
+
+        time.replace(2.0);
+
+        assert_eq!(clock.now(), 2.0);
+    }
+
+    #[test]
+    fn sleep_advances_time_test() {
+        let time = Rc::new(RefCell::new(1.0));
+        let sleeps = Rc::new(RefCell::new(vec![]));
+        let clock = NullableClock::new(time.clone(), sleeps.clone());
+
+        clock.sleep(0.25);
+
+        assert_eq!(*time.borrow(), 1.25);
+        assert_eq!(*sleeps.borrow(), vec![0.25]);
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt::{Display, Formatter};
+use crate::types::mat4::Mat4;
+use crate::types::vec3::{vec3, Vec3};
+use std::hash::{Hash, Hasher};
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3 {
+    pub c0: Vec3,
+    pub c1: Vec3,
+    pub c2: Vec3
+}
+
+impl Hash for Mat3 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.c0.hash(state);
+        self.c1.hash(state);
+        self.c2.hash(state);
+    }
+}
+
+impl Display for Mat3 {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "[{}, {}, {}]", self.c0, self.c1, self.c2)
+    }
+}
+
+impl Index<usize> for Mat3 {
+    type Output = Vec3;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.c0,
+            1 => &self.c1,
+            2 => &self.c2,
+            _ => panic!("Index out of bounds")
+        }
+    }
+}
+
+impl IndexMut<usize> for Mat3 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.c0,
+            1 => &mut self.c1,
+            2 => &mut self.c2,
+            _ => panic!("Index out of bounds")
+        }
+    }
+}
+
+impl From<Mat4> for Mat3 { // the upper left 3x3, e.g. the rotation and scale of a model matrix for normals
+    fn from(mat: Mat4) -> Self {
+        Mat3 {
+            c0: vec3(mat.c0.x, mat.c0.y, mat.c0.z),
+            c1: vec3(mat.c1.x, mat.c1.y, mat.c1.z),
+            c2: vec3(mat.c2.x, mat.c2.y, mat.c2.z)
+        }
+    }
+}
+
+impl From<[f32; 9]> for Mat3 {
+    fn from(array: [f32; 9]) -> Self {
+        Mat3 {
+            c0: vec3(array[0], array[1], array[2]),
+            c1: vec3(array[3], array[4], array[5]),
+            c2: vec3(array[6], array[7], array[8])
+        }
+    }
+}
+
+impl Mat3 {
+    pub fn new(c0: Vec3, c1: Vec3, c2: Vec3) -> Mat3 {
+        Mat3 {
+            c0,
+            c1,
+            c2
+        }
+    }
+
+    pub fn identity() -> Mat3 {
+        Mat3::new(
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            vec3(0.0, 0.0, 1.0)
+        )
+    }
+
+    pub fn as_array(&self) -> [Vec3; 3] {
+        [
+            self.c0,
+            self.c1,
+            self.c2
+        ]
+    }
+}
+
+pub fn mat3(c0: Vec3, c1: Vec3, c2: Vec3) -> Mat3 {
+    Mat3::new(c0, c1, c2)
+}
+
+
+#[cfg(test)]
+mod mat3_test {
+    use crate::types::{mat3, vec3, vec4, Mat3, Mat4};
+
+    #[test]
+    fn from_mat4_test() {
+        let mat = Mat4::new(vec4(1.0, 2.0, 3.0, 4.0), vec4(5.0, 6.0, 7.0, 8.0), vec4(9.0, 10.0, 11.0, 12.0), vec4(13.0, 14.0, 15.0, 16.0));
+
+        assert_eq!(Mat3::from(mat), mat3(vec3(1.0, 2.0, 3.0), vec3(5.0, 6.0, 7.0), vec3(9.0, 10.0, 11.0)));
+        assert_eq!(Mat3::from(Mat4::identity()), Mat3::identity());
+    }
+}
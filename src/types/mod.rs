@@ -5,6 +5,7 @@ mod vec3;
 mod ivec3;
 mod ivec4;
 mod vec4;
+mod mat3;
 mod mat4;
 
 pub use vec2::Vec2;
@@ -21,5 +22,7 @@ pub use vec4::Vec4;
 pub use vec4::vec4;
 pub use ivec4::IVec4;
 pub use ivec4::ivec4;
+pub use mat3::Mat3;
+pub use mat3::mat3;
 pub use mat4::Mat4;
 pub use mat4::mat4;
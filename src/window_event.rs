@@ -0,0 +1,11 @@
+use crate::DisplayMode;
+use crate::types::Vec2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowEvent {
+    Glfw(glfw::WindowEvent), // passed through untouched from GLFW
+    FullscreenChanged { fullscreen: bool, display_mode: DisplayMode },
+    ScreenshotRequested,
+    CloseRequested, // only sent while close interception is on, call confirm_close to actually close
+    ContentScaleChanged { content_scale: Vec2 },
+}
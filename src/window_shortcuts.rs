@@ -0,0 +1,110 @@
+use glfw::{Key, Modifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyCombination {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl KeyCombination {
+    pub fn new(key: Key) -> KeyCombination {
+        KeyCombination { key, modifiers: Modifiers::empty() }
+    }
+
+    pub fn with_modifiers(key: Key, modifiers: Modifiers) -> KeyCombination {
+        KeyCombination { key, modifiers }
+    }
+
+    pub fn matches(&self, key: Key, modifiers: Modifiers) -> bool {
+        let held = modifiers & (Modifiers::Shift | Modifiers::Control | Modifiers::Alt | Modifiers::Super); // lock keys shouldn't stop a shortcut from firing
+
+        self.key == key && held == self.modifiers
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutAction {
+    ToggleFullscreen,
+    Screenshot,
+    Close,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowShortcuts {
+    pub fullscreen_toggle: Option<KeyCombination>,
+    pub screenshot: Option<KeyCombination>,
+    pub close_on_escape: bool,
+}
+
+impl Default for WindowShortcuts {
+    fn default() -> Self {
+        WindowShortcuts {
+            fullscreen_toggle: Some(KeyCombination::new(Key::F11)),
+            screenshot: None,
+            close_on_escape: false,
+        }
+    }
+}
+
+impl WindowShortcuts {
+    pub fn disabled() -> WindowShortcuts {
+        WindowShortcuts {
+            fullscreen_toggle: None,
+            screenshot: None,
+            close_on_escape: false,
+        }
+    }
+
+    pub fn action_for(&self, key: Key, modifiers: Modifiers) -> Option<ShortcutAction> {
+        if self.fullscreen_toggle.is_some_and(|combination| combination.matches(key, modifiers)) {
+            Some(ShortcutAction::ToggleFullscreen)
+        }
+        else if self.screenshot.is_some_and(|combination| combination.matches(key, modifiers)) {
+            Some(ShortcutAction::Screenshot)
+        }
+        else if self.close_on_escape && KeyCombination::new(Key::Escape).matches(key, modifiers) {
+            Some(ShortcutAction::Close)
+        }
+        else {
+            None
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod window_shortcuts_test {
+    use glfw::{Key, Modifiers};
+    use super::{KeyCombination, ShortcutAction, WindowShortcuts};
+
+    #[test]
+    fn default_toggles_fullscreen_on_f11_test() {
+        let shortcuts = WindowShortcuts::default();
+
+        assert_eq!(shortcuts.action_for(Key::F11, Modifiers::empty()), Some(ShortcutAction::ToggleFullscreen));
+        assert_eq!(shortcuts.action_for(Key::Escape, Modifiers::empty()), None);
+    }
+
+    #[test]
+    fn disabled_has_no_actions_test() {
+        let shortcuts = WindowShortcuts::disabled();
+
+        assert_eq!(shortcuts.action_for(Key::F11, Modifiers::empty()), None);
+        assert_eq!(shortcuts.action_for(Key::Escape, Modifiers::empty()), None);
+    }
+
+    #[test]
+    fn combination_requires_modifiers_test() {
+        let shortcuts = WindowShortcuts {
+            fullscreen_toggle: Some(KeyCombination::with_modifiers(Key::Enter, Modifiers::Alt)),
+            screenshot: Some(KeyCombination::new(Key::F12)),
+            close_on_escape: true,
+        };
+
+        assert_eq!(shortcuts.action_for(Key::Enter, Modifiers::empty()), None);
+        assert_eq!(shortcuts.action_for(Key::Enter, Modifiers::Alt | Modifiers::Shift), None);
+        assert_eq!(shortcuts.action_for(Key::Enter, Modifiers::Alt), Some(ShortcutAction::ToggleFullscreen));
+        assert_eq!(shortcuts.action_for(Key::F12, Modifiers::NumLock), Some(ShortcutAction::Screenshot));
+        assert_eq!(shortcuts.action_for(Key::Escape, Modifiers::empty()), Some(ShortcutAction::Close));
+    }
+}